@@ -0,0 +1,187 @@
+//! `il2cpp_bindings!` generates the bindings of a few types of a dump where it is invoked, so small
+//! crates don't need to vendor a whole generated crate:
+//!
+//! ```ignore
+//! il2cpp_bindings::il2cpp_bindings!(
+//!     dump = "codegen.bc",
+//!     types = ["UnityEngine.Transform", "GlobalNamespace.PlayerController"],
+//! );
+//!
+//! fn hook(player: &mut il2cpp::GlobalNamespace::PlayerController) {}
+//! ```
+//!
+//! The types are generated with the types they depend on in a module, `il2cpp` unless it is named
+//! with `module = name`. Generated code refers to its items through `crate::<module>`, so the
+//! macro has to be invoked at the crate root, or given the path of the module with
+//! `root = crate::path::to::module`.
+//!
+//! Paths are relative to the directory of the invoking crate's manifest. `dump` is a JSON dump or
+//! a `.bc` cache of one, and `config = "codegen.toml"` configures the generator like the command
+//! line tool. Unstable features can only be enabled at the crate root, so the invoking crate has
+//! to enable the features of `output.features` itself, or generate code for the stable toolchain
+//! with `output.toolchain = "stable"`.
+//!
+//! The expansion includes the config with `include_bytes!`, so cargo expands the macro again when
+//! it changes. Dumps are too big to be included, so a crate has to tell cargo to rebuild it when
+//! its dump changes from a build script:
+//!
+//! ```ignore
+//! fn main() {
+//!     println!("cargo:rerun-if-changed=codegen.bc");
+//! }
+//! ```
+//!
+//! A crate can invoke the macro several times as long as every invocation has its own module.
+
+#![warn(rust_2018_idioms)]
+
+use il2cpp_codegen::{DllData, Generator};
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{bracketed, parse_macro_input, Ident, LitStr, Token};
+
+struct Args {
+    dump: LitStr,
+    types: Vec<LitStr>,
+    config: Option<LitStr>,
+    module: Option<Ident>,
+    root: Option<syn::Path>,
+}
+
+impl Parse for Args {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let mut dump = None;
+        let mut types = Vec::new();
+        let mut config = None;
+        let mut module = None;
+        let mut root = None;
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            match key.to_string().as_str() {
+                "dump" => dump = Some(input.parse()?),
+                "types" => {
+                    let content;
+                    bracketed!(content in input);
+                    let names = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+                    types = names.into_iter().collect();
+                }
+                "config" => config = Some(input.parse()?),
+                "module" => module = Some(input.parse()?),
+                "root" => root = Some(input.parse()?),
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        format!("unknown argument `{}`", key),
+                    ))
+                }
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(Self {
+            dump: dump.ok_or_else(|| input.error("missing argument `dump`"))?,
+            types,
+            config,
+            module,
+            root,
+        })
+    }
+}
+
+fn error(span: Span, error: impl Display) -> syn::Error {
+    syn::Error::new(span, error)
+}
+
+/// Makes cargo rebuild the invoking crate when the file at `path` changes
+fn track(path: &Path, span: Span) -> syn::Result<TokenStream> {
+    let path = path
+        .canonicalize()
+        .map_err(|e| error(span, format!("{}: {}", path.display(), e)))?;
+    let path = path
+        .to_str()
+        .ok_or_else(|| error(span, format!("{} is not valid UTF-8", path.display())))?;
+    Ok(quote! { const _: &[u8] = include_bytes!(#path); })
+}
+
+/// Resolves a path given to the macro against the directory of the invoking crate
+fn resolve(path: &LitStr) -> syn::Result<PathBuf> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| error(path.span(), "`CARGO_MANIFEST_DIR` is not set"))?;
+    Ok(Path::new(&manifest_dir).join(path.value()))
+}
+
+fn expand(args: Args) -> syn::Result<TokenStream> {
+    let dump_path = resolve(&args.dump)?;
+    let dump = if dump_path
+        .extension()
+        .is_some_and(|extension| extension == "bc")
+    {
+        DllData::from_cache_file(&dump_path)
+    } else {
+        DllData::from_json_file(&dump_path)
+    }
+    .map_err(|e| error(args.dump.span(), format!("{:#}", e)))?;
+
+    let mut tracked = None;
+    let mut generator = match &args.config {
+        Some(config) => {
+            let config_path = resolve(config)?;
+            let generator = Generator::from_config_file(&config_path)
+                .map_err(|e| error(config.span(), format!("{:#}", e)))?;
+            tracked = Some(track(&config_path, config.span())?);
+            generator
+        }
+        None => Generator::new(),
+    };
+    for name in &args.types {
+        let full_name = name.value();
+        let exists = full_name.contains('*')
+            || dump
+                .types
+                .iter()
+                .any(|ty| ty.cs_full_name(&dump) == full_name);
+        if !exists {
+            return Err(error(
+                name.span(),
+                format!("the dump has no type called `{}`", full_name),
+            ));
+        }
+        generator = generator.include(full_name);
+    }
+
+    let module = args
+        .module
+        .unwrap_or_else(|| Ident::new("il2cpp", Span::call_site()));
+    let root = match args.root {
+        Some(root) => root.to_token_stream(),
+        None => quote! { crate::#module },
+    };
+    let generated = generator
+        .root(root.to_string())
+        .generate_tokens(&dump)
+        .map_err(|e| error(Span::call_site(), format!("{:#}", e)))?;
+    let tokens = generated.code;
+    Ok(quote! {
+        #[allow(warnings)]
+        pub mod #module {
+            #tokens
+            #tracked
+        }
+    })
+}
+
+/// Generates the bindings of the given types, see the crate documentation
+#[proc_macro]
+pub fn il2cpp_bindings(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let args = parse_macro_input!(input as Args);
+    expand(args)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
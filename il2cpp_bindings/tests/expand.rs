@@ -0,0 +1,133 @@
+//! Checks that the expansion of the macro compiles, against a runtime which only declares the
+//! items generated code uses
+
+il2cpp_bindings::il2cpp_bindings!(
+    dump = "tests/fixtures/dump.json",
+    types = ["Fixtures.*"],
+    config = "tests/fixtures/codegen.toml",
+);
+
+// Every invocation gets its own module
+il2cpp_bindings::il2cpp_bindings!(
+    dump = "tests/fixtures/dump.json",
+    types = ["Fixtures.I*"],
+    config = "tests/fixtures/codegen.toml",
+    module = fixtures,
+);
+
+#[allow(clippy::missing_safety_doc)]
+mod runtime {
+    pub struct Il2CppObject;
+    pub struct Il2CppException;
+    pub struct Il2CppClass;
+    pub struct MethodInfo;
+
+    /// Arguments are passed as tuples
+    pub trait Arguments {}
+    impl Arguments for () {}
+    impl<A> Arguments for (A,) {}
+    impl<A, B> Arguments for (A, B) {}
+
+    impl MethodInfo {
+        pub fn invoke<T, A: Arguments, R>(
+            &self,
+            _this: T,
+            _args: A,
+        ) -> Result<R, &mut Il2CppException> {
+            unimplemented!()
+        }
+    }
+
+    pub unsafe trait Type: 'static {
+        type Held<'a>;
+        type HeldRaw;
+
+        fn class() -> &'static Il2CppClass;
+    }
+
+    macro_rules! unsafe_impl_type {
+        (in crate::runtime for $ty:ident $(<$($param:ident),*>)? => $($lookup:tt)*) => {
+            unsafe impl $(<$($param: $crate::runtime::Type),*>)? $crate::runtime::Type
+                for $ty $(<$($param),*>)?
+            {
+                type Held<'a> = Option<&'a mut Self>;
+                type HeldRaw = *mut Self;
+
+                fn class() -> &'static $crate::runtime::Il2CppClass {
+                    unimplemented!()
+                }
+            }
+        };
+    }
+    pub(crate) use unsafe_impl_type as unsafe_impl_reference_type;
+
+    pub mod raw {
+        use super::{Il2CppClass, Il2CppException, Il2CppObject, MethodInfo};
+        use std::ffi::c_void;
+        use std::os::raw::c_char;
+
+        pub struct Il2CppImage;
+        pub struct Il2CppType;
+        pub struct Il2CppArray;
+
+        macro_rules! functions {
+            ($( fn $name:ident($($arg:ty),*) $(-> $ret:ty)?; )*) => {
+                $(
+                    pub unsafe fn $name($(_: $arg),*) $(-> $ret)? {
+                        unimplemented!()
+                    }
+                )*
+            };
+        }
+
+        functions! {
+            fn il2cpp_array_length(*mut Il2CppArray) -> u32;
+            fn il2cpp_array_new(*mut Il2CppClass, usize) -> *mut Il2CppArray;
+            fn il2cpp_array_object_header_size() -> u32;
+            fn il2cpp_class_from_system_type(*mut Il2CppObject) -> *mut Il2CppClass;
+            fn il2cpp_class_from_name(*const Il2CppImage, *const c_char, *const c_char)
+                -> *mut Il2CppClass;
+            fn il2cpp_class_get_method_from_name(*mut Il2CppClass, *const c_char, i32)
+                -> *const MethodInfo;
+            fn il2cpp_class_get_methods(*mut Il2CppClass, *mut *mut c_void) -> *const MethodInfo;
+            fn il2cpp_class_get_name(*mut Il2CppClass) -> *const c_char;
+            fn il2cpp_class_get_nested_types(*mut Il2CppClass, *mut *mut c_void)
+                -> *mut Il2CppClass;
+            fn il2cpp_class_get_type(*const Il2CppClass) -> *const Il2CppType;
+            fn il2cpp_exception_from_name_msg(
+                *const Il2CppImage,
+                *const c_char,
+                *const c_char,
+                *const c_char
+            ) -> *mut Il2CppException;
+            fn il2cpp_free(*mut c_void);
+            fn il2cpp_get_corlib() -> *const Il2CppImage;
+            fn il2cpp_method_get_from_reflection(*const Il2CppObject) -> *const MethodInfo;
+            fn il2cpp_method_get_name(*const MethodInfo) -> *const c_char;
+            fn il2cpp_method_get_object(*const MethodInfo, *const Il2CppClass)
+                -> *mut Il2CppObject;
+            fn il2cpp_method_get_param(*const MethodInfo, u32) -> *const Il2CppType;
+            fn il2cpp_method_get_param_count(*const MethodInfo) -> u32;
+            fn il2cpp_method_is_generic(*const MethodInfo) -> bool;
+            fn il2cpp_object_get_class(*mut Il2CppObject) -> *mut Il2CppClass;
+            fn il2cpp_runtime_invoke(
+                *const MethodInfo,
+                *mut c_void,
+                *mut *mut c_void,
+                *mut *mut Il2CppException
+            ) -> *mut Il2CppObject;
+            fn il2cpp_type_get_name(*const Il2CppType) -> *mut c_char;
+            fn il2cpp_type_get_object(*const Il2CppType) -> *mut Il2CppObject;
+        }
+    }
+}
+
+#[test]
+fn generated_types() {
+    fn is_type<T: runtime::Type>() {}
+    is_type::<il2cpp::Fixtures::IFoo>();
+    is_type::<il2cpp::Fixtures::Box_1<il2cpp::Fixtures::IFoo>>();
+    is_type::<il2cpp::Fixtures::Box_1_Entry<il2cpp::Fixtures::IFoo>>();
+    is_type::<il2cpp::Fixtures::Outer_Inner>();
+    is_type::<fixtures::Fixtures::IFoo>();
+}
@@ -0,0 +1,43 @@
+//! Recognition of the types and members generated by the C# compiler, which have mangled names
+//! like `<Start>d__5` or `<>c__DisplayClass12_0` that can't be written in C#
+
+use crate::data::Attribute;
+
+/// Whether a type or member with `name` and `attributes` was generated by the compiler
+pub fn is_compiler_generated(name: &str, attributes: &[Attribute]) -> bool {
+    name.starts_with('<')
+        || attributes
+            .iter()
+            .any(|attribute| attribute.name.ends_with("CompilerGeneratedAttribute"))
+}
+
+/// Derives a readable name from a mangled name, made of the name of the method or property the
+/// item was generated for and a description of its kind: `<Start>d__5` becomes `Start_Iterator`,
+/// `<Start>b__5_0` becomes `Start_Lambda5_0` and `<Speed>k__BackingField` becomes
+/// `Speed_BackingField`. State machines of async methods are called `AsyncStateMachine` instead
+/// of `Iterator`. Returns `None` for names that are not mangled.
+pub fn readable_name(name: &str, is_async: bool) -> Option<String> {
+    let (owner, rest) = name.strip_prefix('<')?.split_once('>')?;
+    let (kind, suffix) = rest.split_once("__").unwrap_or((rest, ""));
+    let description = match kind {
+        // `<Module>` and `<PrivateImplementationDetails>`
+        "" => String::new(),
+        "d" if is_async => "AsyncStateMachine".to_owned(),
+        "d" => "Iterator".to_owned(),
+        // The class holding the lambdas which don't capture anything
+        "c" if suffix.is_empty() => "Lambdas".to_owned(),
+        "b" => format!("Lambda{}", suffix),
+        // Local functions are called `<Owner>g__Name|1_0`
+        "g" => suffix.split('|').next().unwrap().to_owned(),
+        "k" => "BackingField".to_owned(),
+        // Display classes, anonymous types and the fields of state machines carry a descriptive
+        // suffix, like `<>c__DisplayClass12_0` and `<>1__state`
+        _ => suffix.to_owned(),
+    };
+    let readable: Vec<&str> = [owner, description.as_str()]
+        .iter()
+        .copied()
+        .filter(|part| !part.is_empty())
+        .collect();
+    (!readable.is_empty()).then(|| readable.join("_"))
+}
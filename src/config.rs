@@ -0,0 +1,563 @@
+//! Configuration of the generator, read from `codegen.toml`:
+//!
+//! ```toml
+//! [input]
+//! json = "codegen.json"
+//! cache = "codegen.bc"
+//! renames = "renames.json"
+//!
+//! [output]
+//! path = "generated.rs"
+//! nested_modules = true
+//! root = "crate::il2cpp"
+//! toolchain = "stable"
+//!
+//! [filter]
+//! include = ["UnityEngine.*", "GlobalNamespace.PlayerController"]
+//! exclude = ["UnityEngine.Experimental.*"]
+//! compiler_generated = "module"
+//!
+//! [naming]
+//! methods = "snake_case"
+//!
+//! [backend]
+//! profile = "libil2cpp"
+//! object = "Il2CppObject"
+//!
+//! [replacements]
+//! "System.IntPtr" = "isize"
+//! "UnityEngine.Vector3" = { path = "glam::Vec3", size = 12 }
+//! "UnityEngine.Color" = { path = "crate::Color", size = 16, bounds = true }
+//!
+//! [types."GlobalNamespace.PlayerController"]
+//! rename = "Player"
+//! methods.Update = { skip = true }
+//! ```
+//!
+//! Types are referred to by their full name, `Namespace.Name`, with nested types separated from
+//! their declaring type by a `/`: `Namespace.Declaring/Nested`.
+
+use crate::helpers::{is_valid_ident, to_snake_case};
+use anyhow::{bail, Context, Result};
+use serde::de::{self, Deserializer, MapAccess, Visitor};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Options controlling the generated code
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub input: Input,
+    pub output: Output,
+    pub filter: Filter,
+    pub naming: Naming,
+    pub backend: Backend,
+    /// Rust types used in place of il2cpp types, keyed by the full name of the replaced type
+    pub replacements: BTreeMap<String, Replacement>,
+    /// Overrides for single types, keyed by their full name
+    pub types: BTreeMap<String, TypeOverride>,
+    /// Loaded from `input.renames`
+    #[serde(skip)]
+    pub renames: RenameMap,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Input {
+    /// JSON dump created by the dumper
+    pub json: PathBuf,
+    /// Cache of the parsed dump, created when it does not exist yet
+    pub cache: PathBuf,
+    /// JSON rename map for deobfuscating the names of the dump
+    pub renames: Option<PathBuf>,
+    /// Directory with the output of Il2CppDumper, which is imported instead of reading `json`
+    pub il2cpp_dumper: Option<PathBuf>,
+}
+
+impl Default for Input {
+    fn default() -> Self {
+        Self {
+            json: "codegen.json".into(),
+            cache: "codegen.bc".into(),
+            renames: None,
+            il2cpp_dumper: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Output {
+    pub path: PathBuf,
+    /// Emit nested types inside a module named after their declaring type (`Player_::State`)
+    /// instead of flattening their names (`Player_State`). The flattened names are kept as
+    /// aliases.
+    pub nested_modules: bool,
+    /// Toolchain the generated code is compiled with
+    pub toolchain: Toolchain,
+    /// Unstable features enabled in the generated code. Defaults to the features the generated
+    /// code needs on `toolchain`.
+    pub features: Option<Vec<String>>,
+    /// Path of the module the generated code is placed in, which generated items are referred to
+    /// through. Code included in a submodule needs the path of that module, like
+    /// `crate::il2cpp`.
+    pub root: String,
+    /// Stub types and leave out members which can't be generated instead of failing, and list
+    /// them once the code is generated
+    pub keep_going: bool,
+}
+
+impl Output {
+    /// Unstable features enabled in the generated code
+    pub fn features(&self) -> Vec<String> {
+        match &self.features {
+            Some(features) => features.clone(),
+            None => self
+                .toolchain
+                .required_features()
+                .iter()
+                .map(|feature| feature.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl Default for Output {
+    fn default() -> Self {
+        Self {
+            path: "generated.rs".into(),
+            nested_modules: false,
+            toolchain: Toolchain::Nightly,
+            features: None,
+            root: "crate".to_owned(),
+            keep_going: false,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Toolchain {
+    /// Enable the unstable features the generator was written against
+    #[default]
+    Nightly,
+    /// Enable no features. Needs Rust 1.70.
+    Stable,
+}
+
+impl Toolchain {
+    /// Unstable features the generated code needs on the toolchain
+    pub fn required_features(self) -> &'static [&'static str] {
+        match self {
+            Toolchain::Nightly => &["arbitrary_enum_discriminant", "generic_associated_types"],
+            Toolchain::Stable => &[],
+        }
+    }
+}
+
+/// Selection of the types to generate. Patterns match full type names, with `*` matching any
+/// sequence of characters.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Filter {
+    /// Types to generate, all types are generated if this is empty
+    pub include: Vec<String>,
+    /// Types not to generate, even if they are included
+    pub exclude: Vec<String>,
+    /// Also generate the types used by the signatures, interfaces and generic constraints of
+    /// generated types. Members using types that are not generated are left out. Base classes,
+    /// declaring types and the types of instance fields are always generated, as they are needed
+    /// for the layout of a type.
+    pub dependencies: bool,
+    /// What to do with the types and members generated by the C# compiler, like closures,
+    /// iterators and backing fields
+    pub compiler_generated: CompilerGenerated,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self {
+            include: Vec::new(),
+            exclude: Vec::new(),
+            dependencies: true,
+            compiler_generated: CompilerGenerated::Keep,
+        }
+    }
+}
+
+/// Compiler-generated types and members are recognized by their mangled names, like
+/// `<Start>d__5`, and by `CompilerGeneratedAttribute`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompilerGenerated {
+    /// Generate them like all other types and members
+    #[default]
+    Keep,
+    /// Leave them out. Types needed for the layout of generated types are still generated, and
+    /// compiler-generated instance fields are kept as private fields. Both get readable names
+    /// like with `module`.
+    Skip,
+    /// Generate types in a `__compiler_generated` module next to the types they would be
+    /// generated with. Types and members get readable names derived from their mangled names,
+    /// like `Start_Iterator` for `<Start>d__5`.
+    Module,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Naming {
+    pub methods: Case,
+    pub fields: Case,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Case {
+    /// Keep the C# names
+    #[default]
+    Preserve,
+    SnakeCase,
+}
+
+impl Case {
+    pub fn apply(self, name: &str) -> String {
+        match self {
+            Case::Preserve => name.to_owned(),
+            Case::SnakeCase => to_snake_case(name),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TypeOverride {
+    /// Do not generate the type, unless it is needed for the layout of another type
+    pub skip: bool,
+    /// Name of the generated type
+    pub rename: Option<String>,
+    /// Overrides for the methods of the type, keyed by their name. An override applies to all
+    /// overloads of the method.
+    pub methods: BTreeMap<String, MethodOverride>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MethodOverride {
+    pub skip: bool,
+    /// Name of the generated method, which gets the overload suffix appended
+    pub rename: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum Replacement {
+    Path(String),
+    /// The size is required when replacing a value type with anything but a primitive
+    Detailed {
+        path: String,
+        size: Option<i32>,
+        /// Whether to implement the marker traits of generic constraints for the replacement,
+        /// which requires it to implement the runtime's type trait
+        bounds: bool,
+    },
+}
+
+impl Replacement {
+    pub fn path(&self) -> &str {
+        match self {
+            Replacement::Path(path) | Replacement::Detailed { path, .. } => path,
+        }
+    }
+
+    pub fn size(&self) -> Option<i32> {
+        match self {
+            Replacement::Path(_) => None,
+            Replacement::Detailed { size, .. } => *size,
+        }
+    }
+
+    pub fn bounds(&self) -> bool {
+        match self {
+            Replacement::Path(_) => false,
+            Replacement::Detailed { bounds, .. } => *bounds,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DetailedReplacement {
+    path: String,
+    size: Option<i32>,
+    #[serde(default)]
+    bounds: bool,
+}
+
+/// Written by hand instead of using an untagged enum to keep the errors of the detailed form
+impl<'de> Deserialize<'de> for Replacement {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ReplacementVisitor;
+
+        impl<'de> Visitor<'de> for ReplacementVisitor {
+            type Value = Replacement;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a type path or a table with `path`, `size` and `bounds`")
+            }
+
+            fn visit_str<E: de::Error>(self, path: &str) -> Result<Replacement, E> {
+                Ok(Replacement::Path(path.to_owned()))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Replacement, A::Error> {
+                let detailed =
+                    DetailedReplacement::deserialize(de::value::MapAccessDeserializer::new(map))?;
+                Ok(Replacement::Detailed {
+                    path: detailed.path,
+                    size: detailed.size,
+                    bounds: detailed.bounds,
+                })
+            }
+        }
+
+        deserializer.deserialize_any(ReplacementVisitor)
+    }
+}
+
+/// Names used in the generated code in place of the (obfuscated) names of the dump. The il2cpp
+/// names are still used to look up classes and methods at runtime.
+///
+/// ```json
+/// {
+///     "types": { "1234": "PlayerController", "GlobalNamespace.\u0001": "Inventory" },
+///     "methods": { "1234": { "\u0002": "Update" } },
+///     "fields": { "GlobalNamespace.\u0001": { "\u0003": "items" } }
+/// }
+/// ```
+///
+/// Types are keyed by their type definition index or their full name, members by the name they
+/// have in the dump. A method rename applies to all overloads of the method.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RenameMap {
+    pub types: BTreeMap<String, String>,
+    pub methods: BTreeMap<String, BTreeMap<String, String>>,
+    pub fields: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl RenameMap {
+    /// Reads the rename map from the JSON file at `path`
+    pub fn load(path: &Path) -> Result<Self> {
+        let renames = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read rename map {}", path.display()))?;
+        let renames: Self = serde_json::from_str(&renames)
+            .with_context(|| format!("Failed to parse rename map {}", path.display()))?;
+        renames
+            .validate()
+            .with_context(|| format!("Invalid rename map {}", path.display()))?;
+        Ok(renames)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let members = self.methods.iter().chain(&self.fields);
+        let names = self
+            .types
+            .iter()
+            .chain(members.flat_map(|(_, names)| names));
+        for (original, name) in names {
+            if !is_valid_ident(name) {
+                bail!(
+                    "`{}` is renamed to `{}`, which is not an identifier",
+                    original,
+                    name
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Looks up the entry of a type by its type definition index, then by its full name
+    pub fn for_type<'a, T>(
+        map: &'a BTreeMap<String, T>,
+        type_def_index: i32,
+        full_name: &str,
+    ) -> Option<&'a T> {
+        map.get(&type_def_index.to_string())
+            .or_else(|| map.get(full_name))
+    }
+}
+
+/// Runtime crates the generated code can be written against
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Profile {
+    #[default]
+    QuestHook,
+    Libil2cpp,
+}
+
+impl Profile {
+    fn crate_path(self) -> &'static str {
+        match self {
+            Profile::QuestHook => "quest_hook::libil2cpp",
+            Profile::Libil2cpp => "libil2cpp",
+        }
+    }
+}
+
+macro_rules! backend_items {
+    ( $( $(#[$doc:meta])* $name:ident => $default:literal ),* ) => {
+        /// Paths of the runtime items used by the generated code. Items default to their name in
+        /// the crate of the selected profile, and can be configured either relative to the
+        /// runtime crate or as absolute paths.
+        #[derive(Debug, Default, Deserialize)]
+        #[serde(default, deny_unknown_fields)]
+        pub struct Backend {
+            pub profile: Profile,
+            /// Path of the runtime crate, overriding the one of the profile
+            #[serde(rename = "crate")]
+            pub crate_path: Option<String>,
+            $(
+                $(#[$doc])*
+                pub $name: Option<String>,
+            )*
+        }
+
+        impl Backend {
+            $(
+                pub fn $name(&self) -> String {
+                    self.item(&self.$name, $default)
+                }
+            )*
+
+            /// All item paths, by their name in the config
+            pub fn items(&self) -> Vec<(&'static str, String)> {
+                vec![
+                    ("crate", self.crate_path().to_owned()),
+                    $( (stringify!($name), self.$name()) ),*
+                ]
+            }
+        }
+    };
+}
+
+backend_items! {
+    object => "Il2CppObject",
+    string => "Il2CppString",
+    array => "Il2CppArray",
+    exception => "Il2CppException",
+    class => "Il2CppClass",
+    method_info => "MethodInfo",
+    /// Trait implemented by all types il2cpp knows about
+    type_trait => "Type",
+    /// Raw bindings to the il2cpp API
+    raw => "raw",
+    value_type_macro => "unsafe_impl_value_type",
+    reference_type_macro => "unsafe_impl_reference_type"
+}
+
+impl Backend {
+    pub fn crate_path(&self) -> &str {
+        self.crate_path
+            .as_deref()
+            .unwrap_or_else(|| self.profile.crate_path())
+    }
+
+    fn item(&self, configured: &Option<String>, default: &str) -> String {
+        match configured {
+            Some(path) if path.contains("::") => path.clone(),
+            Some(name) => format!("{}::{}", self.crate_path(), name),
+            None => format!("{}::{}", self.crate_path(), default),
+        }
+    }
+}
+
+fn is_valid_path(path: &str) -> bool {
+    syn::parse_str::<syn::Path>(path).is_ok()
+}
+
+fn is_valid_type(path: &str) -> bool {
+    syn::parse_str::<syn::Type>(path).is_ok()
+}
+
+impl Config {
+    /// Reads the configuration from `path`, falling back to the defaults if it does not exist
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let config = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config {}", path.display()))?;
+        let mut config: Self = toml::from_str(&config)
+            .with_context(|| format!("Failed to parse config {}", path.display()))?;
+        config
+            .validate()
+            .with_context(|| format!("Invalid config {}", path.display()))?;
+        if let Some(renames) = &config.input.renames {
+            config.renames = RenameMap::load(renames)?;
+        }
+        Ok(config)
+    }
+
+    /// Checks the values which can't be checked while parsing. Whether the types and methods
+    /// referred to exist is checked when generating.
+    pub fn validate(&self) -> Result<()> {
+        let features = self.output.features.as_deref().unwrap_or_default();
+        for feature in features {
+            if !is_valid_ident(feature) {
+                bail!("`output.features`: `{}` is not a feature name", feature);
+            }
+        }
+        if self.output.toolchain == Toolchain::Stable && !features.is_empty() {
+            bail!("`output.features`: features can't be enabled on the stable toolchain");
+        }
+        if !is_valid_path(&self.output.root) {
+            bail!("`output.root`: `{}` is not a path", self.output.root);
+        }
+        for pattern in self.filter.include.iter().chain(&self.filter.exclude) {
+            if pattern.is_empty() {
+                bail!("`filter`: patterns can't be empty");
+            }
+        }
+        for (name, path) in self.backend.items() {
+            if !is_valid_path(&path) {
+                bail!("`backend.{}`: `{}` is not a valid path", name, path);
+            }
+        }
+        for (ty, replacement) in &self.replacements {
+            if !is_valid_type(replacement.path()) {
+                bail!(
+                    "`replacements.\"{}\"`: `{}` is not a valid type",
+                    ty,
+                    replacement.path()
+                );
+            }
+        }
+        for (ty, type_override) in &self.types {
+            if let Some(rename) = &type_override.rename {
+                if !is_valid_ident(rename) {
+                    bail!(
+                        "`types.\"{}\".rename`: `{}` is not an identifier",
+                        ty,
+                        rename
+                    );
+                }
+            }
+            for (method, method_override) in &type_override.methods {
+                if let Some(rename) = &method_override.rename {
+                    if !is_valid_ident(rename) {
+                        bail!(
+                            "`types.\"{}\".methods.{}.rename`: `{}` is not an identifier",
+                            ty,
+                            method,
+                            rename
+                        );
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
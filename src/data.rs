@@ -1,12 +1,15 @@
 use serde::{Deserialize, Serialize};
-use std::ops::Index;
+use std::convert::TryFrom;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub enum TypeEnum {
     Struct,
     Class,
     Enum,
     Interface,
+    /// A kind added by a newer dumper, which types can't be generated for
+    #[serde(other)]
+    Unknown,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -18,6 +21,8 @@ pub struct TypeDataThis {
     pub is_generic_template: bool,
     pub is_nested: bool,
     pub element_type: Option<TypeRef>,
+    /// Type constraints of all generic parameters of the type together, which is how the dumper
+    /// writes them. It writes none for the generic parameters of methods.
     pub generic_parameter_constraints: Vec<TypeRef>,
     pub generics: Vec<TypeRef>,
     pub declaring_type: Option<TypeRef>,
@@ -28,6 +33,8 @@ pub enum LayoutKind {
     Auto,
     Sequential,
     Explicit,
+    #[serde(other)]
+    Unknown,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -50,7 +57,7 @@ pub struct TypeData {
     pub size: i32,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "PascalCase")]
 pub struct TypeRef {
     pub namespace: String,
@@ -60,6 +67,17 @@ pub struct TypeRef {
     pub is_generic_parameter: bool,
     pub is_array: bool,
     pub is_pointer: bool,
+    /// Type constraints of a generic parameter (`where T : Component`). The dumper doesn't write
+    /// them, so they are taken from the constraints of the type when loading a dump, which is
+    /// only possible for types with a single generic parameter. Parameters of other types and of
+    /// generic methods are unconstrained, unless the dump was edited to give their constraints.
+    /// See `schema`.
+    #[serde(default)]
+    pub generic_parameter_constraints: Vec<TypeRef>,
+    /// Special constraints of a generic parameter: `class`, `struct` or `new()`. Only imports of
+    /// Il2CppDumper output have them, as the dumper doesn't write them.
+    #[serde(default)]
+    pub special_constraints: Vec<String>,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -99,7 +117,7 @@ pub struct Field {
     pub constant: Option<String>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "PascalCase")]
 pub struct Attribute {
     pub name: String,
@@ -116,13 +134,15 @@ pub struct Attribute {
 //     pub value: String,
 // }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub enum ParameterModifier {
     None,
     Ref,
     Out,
     In,
     Params,
+    #[serde(other)]
+    Unknown,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -152,10 +172,121 @@ pub struct DllData {
     pub types: Vec<TypeData>,
 }
 
-impl Index<&TypeRef> for DllData {
-    type Output = TypeData;
+impl TypeData {
+    /// The full C# name of the type, which types are referred to by in the config. Nested types
+    /// are separated from their declaring type by a `/`.
+    pub fn cs_full_name(&self, types: &DllData) -> String {
+        let declaring_types = self.declaring_types(types);
+        let namespace = match declaring_types.first() {
+            Some(outermost) => &outermost.namespace,
+            None => &self.this.namespace,
+        };
+        let mut name = String::new();
+        if !namespace.is_empty() {
+            name.push_str(namespace);
+            name.push('.');
+        }
+        for declaring_type in declaring_types {
+            name.push_str(&declaring_type.name);
+            name.push('/');
+        }
+        name.push_str(&self.this.name);
+        name
+    }
+
+    /// The types this type is nested in, starting with the outermost one. The chain ends early at
+    /// declaring types which are out of range or nested in themselves.
+    pub fn declaring_types<'a>(&'a self, types: &'a DllData) -> Vec<&'a TypeRef> {
+        let mut declaring_types = Vec::new();
+        let mut current = self;
+        while let Some(parent) = &current.this.declaring_type {
+            if declaring_types
+                .iter()
+                .any(|declaring_type: &&TypeRef| declaring_type.type_id == parent.type_id)
+            {
+                break;
+            }
+            declaring_types.insert(0, parent);
+            match types.get(parent) {
+                Some(parent) => current = parent,
+                None => break,
+            }
+        }
+        declaring_types
+    }
+}
+
+impl TypeRef {
+    /// The C# name of the type, including its type arguments
+    pub fn cs_name(&self, types: &DllData) -> String {
+        let mut name = if self.type_id < 0 {
+            self.name.clone()
+        } else if self.name.ends_with(']') || self.name.ends_with('*') {
+            // Array and pointer types are named after their element type
+            match self.namespace.as_str() {
+                "" => self.name.clone(),
+                namespace => format!("{}.{}", namespace, self.name),
+            }
+        } else {
+            match types.get(self) {
+                Some(ty) => ty.cs_full_name(types),
+                None => self.name.clone(),
+            }
+        };
+        if !self.generics.is_empty() {
+            let args: Vec<String> = self.generics.iter().map(|g| g.cs_name(types)).collect();
+            name = format!("{}<{}>", name, args.join(", "));
+        }
+        if self.is_array && !name.ends_with(']') {
+            name.push_str("[]");
+        }
+        if self.is_pointer && !name.ends_with('*') {
+            name.push('*');
+        }
+        name
+    }
+}
+
+impl Method {
+    /// Name and parameter types with their modifiers, which identify an overload
+    pub fn key(&self, types: &DllData) -> String {
+        let params: Vec<String> = self
+            .parameters
+            .iter()
+            .map(|p| {
+                let modifier = match p.modifier {
+                    ParameterModifier::Ref => "ref ",
+                    ParameterModifier::Out => "out ",
+                    ParameterModifier::In => "in ",
+                    ParameterModifier::Params => "params ",
+                    ParameterModifier::None | ParameterModifier::Unknown => "",
+                };
+                format!("{}{}", modifier, p.parameter_type.cs_name(types))
+            })
+            .collect();
+        let generics = if self.generic_parameters.is_empty() {
+            String::new()
+        } else {
+            let names: Vec<&str> = self
+                .generic_parameters
+                .iter()
+                .map(|g| g.name.as_str())
+                .collect();
+            format!("<{}>", names.join(", "))
+        };
+        format!("{}{}({})", self.name, generics, params.join(", "))
+    }
+
+    pub fn signature(&self, types: &DllData) -> String {
+        format!("{} {}", self.return_type.cs_name(types), self.key(types))
+    }
+}
 
-    fn index(&self, type_ref: &TypeRef) -> &Self::Output {
-        &self.types[type_ref.type_id as usize]
+impl DllData {
+    /// The type referred to by `type_ref`, if its type id is in range
+    pub fn get(&self, type_ref: &TypeRef) -> Option<&TypeData> {
+        usize::try_from(type_ref.type_id)
+            .ok()
+            .and_then(|id| self.types.get(id))
     }
 }
@@ -0,0 +1,463 @@
+//! Comparison of two dumps, reporting the API changes between two versions of a game. Types are
+//! matched by their full name, fields by their name and methods by their name and parameter
+//! types. Types or methods sharing their key are matched up in the order of the dumps.
+
+use crate::data::{DllData, Field, Method, TypeData, TypeEnum};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+#[derive(Serialize, Debug, Default)]
+pub struct DiffReport {
+    pub added_types: Vec<String>,
+    pub removed_types: Vec<String>,
+    pub renamed_types: Vec<RenamedType>,
+    pub changed_types: Vec<TypeDiff>,
+}
+
+/// A removed and an added type with the exact same layout and members
+#[derive(Serialize, Debug)]
+pub struct RenamedType {
+    pub old: String,
+    pub new: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TypeDiff {
+    /// Name of the type in the new dump
+    pub name: String,
+    pub changes: Vec<Change>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Change {
+    KindChanged {
+        old: String,
+        new: String,
+    },
+    SizeChanged {
+        old: i32,
+        new: i32,
+    },
+    ParentChanged {
+        old: Option<String>,
+        new: Option<String>,
+    },
+    FieldAdded {
+        name: String,
+        field_type: String,
+        offset: i32,
+        is_static: bool,
+    },
+    FieldRemoved {
+        name: String,
+        field_type: String,
+        offset: i32,
+        is_static: bool,
+    },
+    FieldTypeChanged {
+        name: String,
+        old: String,
+        new: String,
+    },
+    FieldOffsetChanged {
+        name: String,
+        old: i32,
+        new: i32,
+    },
+    MethodAdded {
+        signature: String,
+    },
+    MethodRemoved {
+        signature: String,
+    },
+    /// The only overload of a method with this name changed its parameter or return types
+    MethodSignatureChanged {
+        name: String,
+        old: String,
+        new: String,
+    },
+    MethodRvaChanged {
+        signature: String,
+        old: i32,
+        new: i32,
+    },
+    MethodSlotChanged {
+        signature: String,
+        old: i32,
+        new: i32,
+    },
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let static_prefix = |is_static: &bool| if *is_static { "static " } else { "" };
+        let type_name = |name: &Option<String>| name.clone().unwrap_or_else(|| "none".to_owned());
+        match self {
+            Change::KindChanged { old, new } => write!(f, "~ kind: {} -> {}", old, new),
+            Change::SizeChanged { old, new } => write!(f, "~ size: {:#X} -> {:#X}", old, new),
+            Change::ParentChanged { old, new } => {
+                write!(f, "~ parent: {} -> {}", type_name(old), type_name(new))
+            }
+            Change::FieldAdded {
+                name,
+                field_type,
+                offset,
+                is_static,
+            } => write!(
+                f,
+                "+ {}field {} {} @ {:#X}",
+                static_prefix(is_static),
+                field_type,
+                name,
+                offset
+            ),
+            Change::FieldRemoved {
+                name,
+                field_type,
+                offset,
+                is_static,
+            } => write!(
+                f,
+                "- {}field {} {} @ {:#X}",
+                static_prefix(is_static),
+                field_type,
+                name,
+                offset
+            ),
+            Change::FieldTypeChanged { name, old, new } => {
+                write!(f, "~ field {}: type {} -> {}", name, old, new)
+            }
+            Change::FieldOffsetChanged { name, old, new } => {
+                write!(f, "~ field {}: offset {:#X} -> {:#X}", name, old, new)
+            }
+            Change::MethodAdded { signature } => write!(f, "+ method {}", signature),
+            Change::MethodRemoved { signature } => write!(f, "- method {}", signature),
+            Change::MethodSignatureChanged { name, old, new } => {
+                write!(f, "~ method {}: {} -> {}", name, old, new)
+            }
+            Change::MethodRvaChanged {
+                signature,
+                old,
+                new,
+            } => {
+                write!(f, "~ method {}: RVA {:#X} -> {:#X}", signature, old, new)
+            }
+            Change::MethodSlotChanged {
+                signature,
+                old,
+                new,
+            } => {
+                write!(f, "~ method {}: slot {} -> {}", signature, old, new)
+            }
+        }
+    }
+}
+
+impl fmt::Display for DiffReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for name in &self.added_types {
+            writeln!(f, "+ type {}", name)?;
+        }
+        for name in &self.removed_types {
+            writeln!(f, "- type {}", name)?;
+        }
+        for renamed in &self.renamed_types {
+            writeln!(f, "~ type {} -> {}", renamed.old, renamed.new)?;
+        }
+        for type_diff in &self.changed_types {
+            writeln!(f, "~ type {}", type_diff.name)?;
+            for change in &type_diff.changes {
+                writeln!(f, "    {}", change)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn kind_name(type_enum: &TypeEnum) -> String {
+    format!("{:?}", type_enum).to_lowercase()
+}
+
+/// Everything that identifies a type apart from its name, used to recognize renamed types
+fn shape(ty: &TypeData, types: &DllData) -> String {
+    let mut shape = format!(
+        "{} {} {:?}",
+        kind_name(&ty.type_enum),
+        ty.size,
+        ty.parent.as_ref().map(|p| p.cs_name(types))
+    );
+    for field in ty.instance_fields.iter().chain(&ty.static_fields) {
+        shape += &format!(
+            " {}:{}@{}",
+            field.name,
+            field.field_type.cs_name(types),
+            field.offset
+        );
+    }
+    let mut methods: Vec<String> = ty.methods.iter().map(|m| m.signature(types)).collect();
+    methods.sort();
+    shape += &methods.join(";");
+    shape
+}
+
+fn types_by_name(dump: &DllData) -> BTreeMap<String, Vec<&TypeData>> {
+    let mut types: BTreeMap<String, Vec<&TypeData>> = BTreeMap::new();
+    for ty in &dump.types {
+        types.entry(ty.cs_full_name(dump)).or_default().push(ty);
+    }
+    types
+}
+
+fn fields_by_name(ty: &TypeData) -> BTreeMap<&str, (&Field, bool)> {
+    let instance_fields = ty.instance_fields.iter().map(|f| (f, false));
+    let static_fields = ty.static_fields.iter().map(|f| (f, true));
+    instance_fields
+        .chain(static_fields)
+        .map(|(f, is_static)| (f.name.as_str(), (f, is_static)))
+        .collect()
+}
+
+fn methods_by_key<'a>(ty: &'a TypeData, types: &DllData) -> BTreeMap<String, Vec<&'a Method>> {
+    let mut methods: BTreeMap<String, Vec<&Method>> = BTreeMap::new();
+    for method in &ty.methods {
+        methods.entry(method.key(types)).or_default().push(method);
+    }
+    methods
+}
+
+fn diff_fields(
+    old: &TypeData,
+    old_dump: &DllData,
+    new: &TypeData,
+    new_dump: &DllData,
+) -> Vec<Change> {
+    let mut changes = Vec::new();
+    let old_fields = fields_by_name(old);
+    let new_fields = fields_by_name(new);
+    for (name, (old_field, is_static)) in &old_fields {
+        match new_fields.get(name) {
+            Some((new_field, _)) => {
+                let old_type = old_field.field_type.cs_name(old_dump);
+                let new_type = new_field.field_type.cs_name(new_dump);
+                if old_type != new_type {
+                    changes.push(Change::FieldTypeChanged {
+                        name: name.to_string(),
+                        old: old_type,
+                        new: new_type,
+                    });
+                }
+                if old_field.offset != new_field.offset {
+                    changes.push(Change::FieldOffsetChanged {
+                        name: name.to_string(),
+                        old: old_field.offset,
+                        new: new_field.offset,
+                    });
+                }
+            }
+            None => changes.push(Change::FieldRemoved {
+                name: name.to_string(),
+                field_type: old_field.field_type.cs_name(old_dump),
+                offset: old_field.offset,
+                is_static: *is_static,
+            }),
+        }
+    }
+    for (name, (new_field, is_static)) in &new_fields {
+        if !old_fields.contains_key(name) {
+            changes.push(Change::FieldAdded {
+                name: name.to_string(),
+                field_type: new_field.field_type.cs_name(new_dump),
+                offset: new_field.offset,
+                is_static: *is_static,
+            });
+        }
+    }
+    changes
+}
+
+fn diff_methods(
+    old: &TypeData,
+    old_dump: &DllData,
+    new: &TypeData,
+    new_dump: &DllData,
+) -> Vec<Change> {
+    let mut changes = Vec::new();
+    let old_methods = methods_by_key(old, old_dump);
+    let new_methods = methods_by_key(new, new_dump);
+
+    // Methods whose overloads did not match up, grouped by name. A method with a single overload
+    // on both sides changed its signature, anything else is reported as added and removed.
+    let mut unmatched: BTreeMap<&str, (Vec<&Method>, Vec<&Method>)> = BTreeMap::new();
+    for (key, old_overloads) in &old_methods {
+        let new_overloads = new_methods.get(key).map(Vec::as_slice).unwrap_or_default();
+        for &method in old_overloads.iter().skip(new_overloads.len()) {
+            unmatched.entry(&method.name).or_default().0.push(method);
+        }
+        for (old_method, new_method) in old_overloads.iter().zip(new_overloads) {
+            changes.extend(diff_method(old_method, old_dump, new_method, new_dump));
+        }
+    }
+    for (key, new_overloads) in &new_methods {
+        let matched = old_methods.get(key).map_or(0, Vec::len);
+        for &method in new_overloads.iter().skip(matched) {
+            unmatched.entry(&method.name).or_default().1.push(method);
+        }
+    }
+    for (name, (removed, added)) in unmatched {
+        if let ([old_method], [new_method]) = (&removed[..], &added[..]) {
+            changes.push(Change::MethodSignatureChanged {
+                name: name.to_owned(),
+                old: old_method.signature(old_dump),
+                new: new_method.signature(new_dump),
+            });
+            continue;
+        }
+        for method in removed {
+            changes.push(Change::MethodRemoved {
+                signature: method.signature(old_dump),
+            });
+        }
+        for method in added {
+            changes.push(Change::MethodAdded {
+                signature: method.signature(new_dump),
+            });
+        }
+    }
+    changes
+}
+
+fn diff_method(
+    old_method: &Method,
+    old_dump: &DllData,
+    new_method: &Method,
+    new_dump: &DllData,
+) -> Vec<Change> {
+    let mut changes = Vec::new();
+    let old_signature = old_method.signature(old_dump);
+    let new_signature = new_method.signature(new_dump);
+    if old_signature != new_signature {
+        changes.push(Change::MethodSignatureChanged {
+            name: old_method.name.clone(),
+            old: old_signature,
+            new: new_signature.clone(),
+        });
+    }
+    if old_method.rva != new_method.rva {
+        changes.push(Change::MethodRvaChanged {
+            signature: new_signature.clone(),
+            old: old_method.rva,
+            new: new_method.rva,
+        });
+    }
+    if old_method.slot != new_method.slot {
+        changes.push(Change::MethodSlotChanged {
+            signature: new_signature,
+            old: old_method.slot,
+            new: new_method.slot,
+        });
+    }
+    changes
+}
+
+fn diff_type(
+    old: &TypeData,
+    old_dump: &DllData,
+    new: &TypeData,
+    new_dump: &DllData,
+) -> Vec<Change> {
+    let mut changes = Vec::new();
+    let (old_kind, new_kind) = (kind_name(&old.type_enum), kind_name(&new.type_enum));
+    if old_kind != new_kind {
+        changes.push(Change::KindChanged {
+            old: old_kind,
+            new: new_kind,
+        });
+    }
+    if old.size != new.size {
+        changes.push(Change::SizeChanged {
+            old: old.size,
+            new: new.size,
+        });
+    }
+    let old_parent = old.parent.as_ref().map(|p| p.cs_name(old_dump));
+    let new_parent = new.parent.as_ref().map(|p| p.cs_name(new_dump));
+    if old_parent != new_parent {
+        changes.push(Change::ParentChanged {
+            old: old_parent,
+            new: new_parent,
+        });
+    }
+    changes.extend(diff_fields(old, old_dump, new, new_dump));
+    changes.extend(diff_methods(old, old_dump, new, new_dump));
+    changes
+}
+
+pub fn diff(old_dump: &DllData, new_dump: &DllData) -> DiffReport {
+    let old_types = types_by_name(old_dump);
+    let new_types = types_by_name(new_dump);
+    let mut report = DiffReport::default();
+
+    let mut removed: Vec<(&String, &TypeData)> = Vec::new();
+    for (name, old_tys) in &old_types {
+        let new_tys = new_types.get(name).map(Vec::as_slice).unwrap_or_default();
+        for (old_ty, new_ty) in old_tys.iter().zip(new_tys) {
+            let changes = diff_type(old_ty, old_dump, new_ty, new_dump);
+            if !changes.is_empty() {
+                report.changed_types.push(TypeDiff {
+                    name: name.clone(),
+                    changes,
+                });
+            }
+        }
+        removed.extend(old_tys.iter().skip(new_tys.len()).map(|ty| (name, *ty)));
+    }
+    let mut added: Vec<(&String, &TypeData)> = Vec::new();
+    for (name, new_tys) in &new_types {
+        let matched = old_types.get(name).map_or(0, Vec::len);
+        added.extend(new_tys.iter().skip(matched).map(|ty| (name, *ty)));
+    }
+
+    // A type is only considered renamed if its shape is unique among both the removed and the
+    // added types
+    let mut removed_shapes: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (i, (_, ty)) in removed.iter().enumerate() {
+        removed_shapes
+            .entry(shape(ty, old_dump))
+            .or_default()
+            .push(i);
+    }
+    let mut added_shapes: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (i, (_, ty)) in added.iter().enumerate() {
+        added_shapes.entry(shape(ty, new_dump)).or_default().push(i);
+    }
+    let mut renamed_old = BTreeSet::new();
+    let mut renamed_new = BTreeSet::new();
+    for (shape, old_ids) in &removed_shapes {
+        if let (Some(&[new_id]), &[old_id]) =
+            (added_shapes.get(shape).map(Vec::as_slice), &old_ids[..])
+        {
+            report.renamed_types.push(RenamedType {
+                old: removed[old_id].0.clone(),
+                new: added[new_id].0.clone(),
+            });
+            renamed_old.insert(old_id);
+            renamed_new.insert(new_id);
+        }
+    }
+
+    report.removed_types = removed
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !renamed_old.contains(i))
+        .map(|(_, (name, _))| name.to_string())
+        .collect();
+    report.added_types = added
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !renamed_new.contains(i))
+        .map(|(_, (name, _))| name.to_string())
+        .collect();
+    report
+}
@@ -0,0 +1,118 @@
+//! Errors for the items of a dump which can't be generated. When generation keeps going, types
+//! which can't be generated are replaced by opaque stubs and members are left out.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemberKind {
+    Field,
+    Constant,
+    Method,
+}
+
+impl fmt::Display for MemberKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MemberKind::Field => "field",
+            MemberKind::Constant => "constant",
+            MemberKind::Method => "method",
+        })
+    }
+}
+
+/// Why an item can't be generated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// A type reference whose type id is not the index of a type of the dump
+    TypeIdOutOfRange(i32),
+    /// A type which was given no identifier, because it is not generated
+    UnnamedType(usize),
+    /// A nested type with fewer generic parameters than its declaring type, which nested types
+    /// take their leading generic parameters from
+    MissingDeclaringGenerics { expected: usize, found: usize },
+    /// An enum without the instance field holding its value
+    MissingEnumValue,
+    /// A type whose kind the dump reports as something else than a class, struct, enum or
+    /// interface
+    UnknownTypeKind,
+    /// A constant without a value, or with a value that is not valid Rust code
+    InvalidConstant(Option<String>),
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorKind::TypeIdOutOfRange(type_id) => {
+                write!(f, "refers to type id {}, which is out of range", type_id)
+            }
+            ErrorKind::UnnamedType(type_id) => {
+                write!(f, "refers to type id {}, which was given no name", type_id)
+            }
+            ErrorKind::MissingDeclaringGenerics { expected, found } => write!(
+                f,
+                "the declaring type has {} generic parameters, but the nested type only {}",
+                expected, found
+            ),
+            ErrorKind::MissingEnumValue => write!(f, "the enum has no value field"),
+            ErrorKind::UnknownTypeKind => write!(f, "the kind of the type is unknown"),
+            ErrorKind::InvalidConstant(Some(value)) => {
+                write!(f, "`{}` is not a valid constant", value)
+            }
+            ErrorKind::InvalidConstant(None) => write!(f, "the constant has no value"),
+        }
+    }
+}
+
+/// A type or member of the dump which can't be generated
+#[derive(Debug, Clone)]
+pub struct GenerateError {
+    /// Full C# name of the type, or of the declaring type of the member
+    pub type_name: String,
+    /// The member which can't be generated, unless the type itself can't be
+    pub member: Option<(MemberKind, String)>,
+    pub kind: ErrorKind,
+}
+
+impl GenerateError {
+    pub fn for_type(type_name: impl Into<String>, kind: ErrorKind) -> Self {
+        Self {
+            type_name: type_name.into(),
+            member: None,
+            kind,
+        }
+    }
+
+    pub fn for_member(
+        type_name: impl Into<String>,
+        member_kind: MemberKind,
+        member: impl Into<String>,
+        kind: ErrorKind,
+    ) -> Self {
+        Self {
+            type_name: type_name.into(),
+            member: Some((member_kind, member.into())),
+            kind,
+        }
+    }
+
+    /// Whether the type has to be stubbed when generation keeps going, rather than only leaving
+    /// out the member. Fields are needed for the layout of their type.
+    pub fn stubs_type(&self) -> bool {
+        matches!(self.member, None | Some((MemberKind::Field, _)))
+    }
+}
+
+impl fmt::Display for GenerateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.member {
+            Some((member_kind, member)) => write!(
+                f,
+                "{} `{}.{}`: {}",
+                member_kind, self.type_name, member, self.kind
+            ),
+            None => write!(f, "type `{}`: {}", self.type_name, self.kind),
+        }
+    }
+}
+
+impl std::error::Error for GenerateError {}
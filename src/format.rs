@@ -0,0 +1,61 @@
+//! Formatting of the generated code, so it can be read and diffed without running rustfmt
+
+use anyhow::{Context, Result};
+use proc_macro2::TokenStream;
+
+/// Starts of lines which begin an item
+const ITEM_STARTS: &[&str] = &[
+    "#[",
+    "///",
+    "//!",
+    "pub ",
+    "impl",
+    "unsafe impl",
+    "fn ",
+    "unsafe fn ",
+    "const ",
+    "static ",
+    "struct ",
+    "mod ",
+    "type ",
+];
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn is_item_start(line: &str) -> bool {
+    let line = line.trim_start();
+    // Invocations of the runtime's macros, which are written with their full path
+    let is_macro_item = line
+        .split_once("!(")
+        .is_some_and(|(path, _)| path.contains("::") && !path.contains(' '));
+    ITEM_STARTS.iter().any(|start| line.starts_with(start)) || is_macro_item
+}
+
+/// Pretty-prints `tokens` as a file. Items are separated by blank lines, which prettyplease does
+/// not emit.
+pub fn format_code(tokens: TokenStream) -> Result<String> {
+    let file = syn::parse2(tokens).context("Failed to parse generated code")?;
+    let code = prettyplease::unparse(&file);
+
+    let mut formatted = String::with_capacity(code.len() + code.len() / 16);
+    let mut previous: Option<&str> = None;
+    for line in code.lines() {
+        if let Some(previous) = previous {
+            let previous_ends_item =
+                previous.ends_with('}') || previous.ends_with(';') || previous.starts_with("#![");
+            if previous_ends_item
+                && indentation(previous) == indentation(line)
+                && is_item_start(line)
+                && !line.starts_with("#![")
+            {
+                formatted.push('\n');
+            }
+        }
+        formatted.push_str(line);
+        formatted.push('\n');
+        previous = Some(line);
+    }
+    Ok(formatted)
+}
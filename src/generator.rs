@@ -0,0 +1,139 @@
+//! The entry point for generating bindings from other crates, like from a build script
+
+use crate::config::{Config, RenameMap, Toolchain};
+use crate::data::DllData;
+use crate::error::GenerateError;
+use crate::format;
+use crate::names::Rename;
+use anyhow::{bail, Context, Result};
+use proc_macro2::TokenStream;
+use std::fs;
+use std::path::Path;
+
+/// Bindings generated for a dump, along with what had to be changed or left out to generate them
+#[derive(Debug)]
+pub struct Generated<T> {
+    pub code: T,
+    /// Items which could not be generated and were stubbed or left out, when generation keeps
+    /// going
+    pub skipped: Vec<GenerateError>,
+    /// Identifiers which were suffixed because their names collided
+    pub renames: Vec<Rename>,
+}
+
+/// Generates bindings for the types of a dump. A generator starts from a configuration like the
+/// `codegen.toml` of the command line tool, which the builder methods change. Generating does not
+/// consume the generator, so one generator can write bindings for several dumps.
+#[derive(Debug, Default)]
+pub struct Generator {
+    config: Config,
+}
+
+impl Generator {
+    /// A generator with the default configuration, which generates all types of the dump
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_config(config: Config) -> Self {
+        Self { config }
+    }
+
+    /// Reads the configuration from a `codegen.toml`
+    pub fn from_config_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            bail!("Config {} does not exist", path.display());
+        }
+        Config::load(path).map(Self::with_config)
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn config_mut(&mut self) -> &mut Config {
+        &mut self.config
+    }
+
+    /// Generates the types matching `pattern`, a full type name in which `*` matches any sequence
+    /// of characters. If no pattern is included, all types are generated.
+    pub fn include(mut self, pattern: impl Into<String>) -> Self {
+        self.config.filter.include.push(pattern.into());
+        self
+    }
+
+    /// Leaves out the types matching `pattern`, unless they are needed by generated types
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        self.config.filter.exclude.push(pattern.into());
+        self
+    }
+
+    /// Whether to also generate the types used by the signatures, interfaces and generic
+    /// constraints of the included types
+    pub fn dependencies(mut self, dependencies: bool) -> Self {
+        self.config.filter.dependencies = dependencies;
+        self
+    }
+
+    /// Whether to emit nested types inside a module named after their declaring type
+    pub fn nested_modules(mut self, nested_modules: bool) -> Self {
+        self.config.output.nested_modules = nested_modules;
+        self
+    }
+
+    /// Toolchain the generated code is compiled with, nightly by default
+    pub fn toolchain(mut self, toolchain: Toolchain) -> Self {
+        self.config.output.toolchain = toolchain;
+        self
+    }
+
+    /// Whether to stub types and leave out members which can't be generated instead of failing
+    pub fn keep_going(mut self, keep_going: bool) -> Self {
+        self.config.output.keep_going = keep_going;
+        self
+    }
+
+    /// Path of the module the generated code is placed in, `crate` by default
+    pub fn root(mut self, root: impl Into<String>) -> Self {
+        self.config.output.root = root.into();
+        self
+    }
+
+    /// Reads the JSON rename map at `path`, which gives types and members Rust names
+    pub fn renames(mut self, path: impl AsRef<Path>) -> Result<Self> {
+        self.config.renames = RenameMap::load(path.as_ref())?;
+        Ok(self)
+    }
+
+    /// Generates the bindings as tokens, which can be returned from a procedural macro
+    pub fn generate_tokens(&self, dump: &DllData) -> Result<Generated<TokenStream>> {
+        self.config.validate().context("Invalid config")?;
+        dump.write_tokens(&self.config)
+    }
+
+    /// Generates the bindings as formatted code. The code has no inner attributes, so the
+    /// features of the toolchain have to be enabled by the crate including it.
+    pub fn generate(&self, dump: &DllData) -> Result<Generated<String>> {
+        let generated = self.generate_tokens(dump)?;
+        Ok(Generated {
+            code: format::format_code(generated.code)?,
+            skipped: generated.skipped,
+            renames: generated.renames,
+        })
+    }
+
+    /// Generates the bindings and writes them to `path`, like `$OUT_DIR/bindings.rs` to be
+    /// `include!`d
+    pub fn write_to(&self, dump: &DllData, path: impl AsRef<Path>) -> Result<Generated<()>> {
+        let path = path.as_ref();
+        let generated = self.generate(dump)?;
+        fs::write(path, generated.code)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(Generated {
+            code: (),
+            skipped: generated.skipped,
+            renames: generated.renames,
+        })
+    }
+}
@@ -1,6 +1,7 @@
 use proc_macro2::{Ident, Span};
 use unicode_xid::UnicodeXID;
 
+#[allow(clippy::manual_is_ascii_check)]
 pub fn is_ident_start(c: char) -> bool {
     ('a'..='z').contains(&c)
         || ('A'..='Z').contains(&c)
@@ -8,6 +9,7 @@ pub fn is_ident_start(c: char) -> bool {
         || (c > '\x7f' && UnicodeXID::is_xid_start(c))
 }
 
+#[allow(clippy::manual_is_ascii_check)]
 pub fn is_ident_continue(c: char) -> bool {
     ('a'..='z').contains(&c)
         || ('A'..='Z').contains(&c)
@@ -29,14 +31,15 @@ const RESTRICTED_KEYWORDS: &[&str] = &[
 pub fn fix_ident(ident: &str) -> String {
     let mut new = String::new();
     let mut chars = ident.chars();
-    let first = chars.next().unwrap();
-    if !is_ident_start(first) {
-        new.push('_');
-        if is_ident_continue(first) {
-            new.push(first);
+    match chars.next() {
+        Some(first) if is_ident_start(first) => new.push(first),
+        Some(first) => {
+            new.push('_');
+            if is_ident_continue(first) {
+                new.push(first);
+            }
         }
-    } else {
-        new.push(first);
+        None => new.push('_'),
     }
     for ch in chars {
         if !is_ident_continue(ch) {
@@ -55,6 +58,60 @@ pub fn create_ident(string: &str) -> Ident {
     Ident::new(&fix_ident(string), Span::call_site())
 }
 
-pub fn create_ident_trimmed(string: &str) -> Ident {
-    create_ident(string.trim_start_matches('_'))
+/// Whether `string` can be used as an identifier as it is
+pub fn is_valid_ident(string: &str) -> bool {
+    let mut chars = string.chars();
+    match chars.next() {
+        Some(first) => {
+            is_ident_start(first)
+                && chars.all(is_ident_continue)
+                && !RESTRICTED_KEYWORDS.contains(&string)
+        }
+        None => false,
+    }
+}
+
+/// Converts a C# name in PascalCase or camelCase to snake_case. Acronyms are kept together, so
+/// `GetHTTPResponse` becomes `get_http_response`.
+pub fn to_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake_case = String::with_capacity(name.len() + 4);
+    for (i, &ch) in chars.iter().enumerate() {
+        if ch.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_is_lower) {
+                snake_case.push('_');
+            }
+        }
+        snake_case.extend(ch.to_lowercase());
+    }
+    snake_case
+}
+
+/// Matches `name` against a pattern in which `*` stands for any sequence of characters
+pub fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let mut rest = match name.strip_prefix(parts.next().unwrap()) {
+        Some(rest) => rest,
+        None => return false,
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let last = match parts.pop() {
+        Some(last) => last,
+        None => return rest.is_empty(),
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(start) => rest = &rest[start + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Whether `name` has no characters that can be kept in an identifier, in which case `fix_ident`
+/// would only produce underscores
+pub fn is_unnamable(name: &str) -> bool {
+    !name.chars().any(|c| c != '_' && is_ident_continue(c))
 }
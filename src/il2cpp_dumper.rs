@@ -0,0 +1,1365 @@
+//! Import of the output of Il2CppDumper, for games where the dumper can't be run. Types, fields
+//! and methods are read from `dump.cs`. Addresses of methods are taken from `script.json` when
+//! `dump.cs` was written without them. `il2cpp.h` is not needed, as `dump.cs` has the offsets of
+//! fields.
+//!
+//! `dump.cs` doesn't qualify type names with their namespace, so a type name is resolved to the
+//! type of that name whose namespace is closest to the one of the type it is used in. Sizes of
+//! types are not part of it either and are estimated from their fields. `validate` helps finding
+//! where either went wrong.
+
+use crate::data::{
+    Attribute, DllData, Field, LayoutKind, Method, Parameter, ParameterModifier, Property,
+    TypeData, TypeDataThis, TypeEnum, TypeRef,
+};
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap};
+use std::convert::TryFrom;
+use std::fs;
+use std::path::Path;
+
+/// Size of the header of objects, which `dump.cs` leaves out of the offsets of the fields of value
+/// types, while dumps of the dumper include it
+const OBJECT_HEADER_SIZE: i32 = 16;
+
+const POINTER_SIZE: i32 = 8;
+
+/// The types of `System` which C# has keywords for
+const KEYWORD_TYPES: &[(&str, &str)] = &[
+    ("bool", "Boolean"),
+    ("byte", "Byte"),
+    ("sbyte", "SByte"),
+    ("char", "Char"),
+    ("decimal", "Decimal"),
+    ("double", "Double"),
+    ("float", "Single"),
+    ("int", "Int32"),
+    ("uint", "UInt32"),
+    ("long", "Int64"),
+    ("ulong", "UInt64"),
+    ("short", "Int16"),
+    ("ushort", "UInt16"),
+    ("object", "Object"),
+    ("string", "String"),
+    ("void", "Void"),
+];
+
+/// Sizes of the values of the primitive types of `System`, which are defined with a field of their
+/// own type
+const PRIMITIVE_SIZES: &[(&str, i32)] = &[
+    ("Boolean", 1),
+    ("Byte", 1),
+    ("SByte", 1),
+    ("Char", 2),
+    ("Int16", 2),
+    ("UInt16", 2),
+    ("Int32", 4),
+    ("UInt32", 4),
+    ("Single", 4),
+    ("Int64", 8),
+    ("UInt64", 8),
+    ("Double", 8),
+    ("IntPtr", 8),
+    ("UIntPtr", 8),
+    ("Void", 1),
+];
+
+const MODIFIERS: &[&str] = &[
+    "public",
+    "private",
+    "protected",
+    "internal",
+    "static",
+    "readonly",
+    "const",
+    "volatile",
+    "virtual",
+    "override",
+    "abstract",
+    "sealed",
+    "extern",
+    "new",
+    "unsafe",
+    "async",
+    "event",
+    "fixed",
+];
+
+/// A type as written in `dump.cs`, like `Dictionary<int, string>[]`
+#[derive(Debug, Clone)]
+struct TypeExpr {
+    /// Name of the type, with the names of its declaring types separated by `.`
+    path: String,
+    /// Type arguments, including those of the declaring types
+    args: Vec<TypeExpr>,
+    suffixes: Vec<Suffix>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Suffix {
+    /// `[]`, `[,]`, ... with their rank
+    Array(usize),
+    Pointer,
+    Nullable,
+}
+
+impl TypeExpr {
+    fn parse(s: &str) -> Option<Self> {
+        let mut parser = ExprParser {
+            s: s.trim(),
+            pos: 0,
+        };
+        let expr = parser.expr()?;
+        parser.skip_spaces();
+        (parser.pos == parser.s.len()).then_some(expr)
+    }
+}
+
+struct ExprParser<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl ExprParser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.s[self.pos..].chars().next()
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek() == Some(' ') {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_spaces();
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// A name, which may start with a compiler generated part like `<>c` or `<Start>d__5`
+    fn name(&mut self) -> Option<&str> {
+        let start = self.pos;
+        if self.peek() == Some('<') {
+            let mut depth = 0;
+            while let Some(c) = self.peek() {
+                self.pos += c.len_utf8();
+                match c {
+                    '<' => depth += 1,
+                    '>' => depth -= 1,
+                    _ => {}
+                }
+                if depth == 0 {
+                    break;
+                }
+            }
+        }
+        while let Some(c) = self.peek() {
+            if " <>[],*?().:".contains(c) {
+                break;
+            }
+            self.pos += c.len_utf8();
+        }
+        (self.pos > start).then(|| &self.s[start..self.pos])
+    }
+
+    fn expr(&mut self) -> Option<TypeExpr> {
+        self.skip_spaces();
+        let mut path = String::new();
+        let mut args = Vec::new();
+        loop {
+            path.push_str(self.name()?);
+            if self.peek() == Some('<') {
+                self.pos += 1;
+                loop {
+                    self.skip_spaces();
+                    // Variance of the parameters of generic interfaces and delegates
+                    for variance in ["in ", "out "].iter() {
+                        if self.s[self.pos..].starts_with(variance) {
+                            self.pos += variance.len();
+                        }
+                    }
+                    args.push(self.expr()?);
+                    if self.eat('>') {
+                        break;
+                    }
+                    if !self.eat(',') {
+                        return None;
+                    }
+                }
+            }
+            if self.peek() != Some('.') {
+                break;
+            }
+            self.pos += 1;
+            path.push('.');
+        }
+
+        let mut suffixes = Vec::new();
+        loop {
+            if self.eat('[') {
+                let mut rank = 1;
+                while self.eat(',') {
+                    rank += 1;
+                }
+                if !self.eat(']') {
+                    return None;
+                }
+                suffixes.push(Suffix::Array(rank));
+            } else if self.eat('*') {
+                suffixes.push(Suffix::Pointer);
+            } else if self.eat('?') {
+                suffixes.push(Suffix::Nullable);
+            } else {
+                break;
+            }
+        }
+        Some(TypeExpr {
+            path,
+            args,
+            suffixes,
+        })
+    }
+}
+
+/// Splits `s` at the occurrences of `separator` which are not within brackets
+fn split_top_level(s: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '<' | '[' | '(' => depth += 1,
+            '>' | ']' | ')' => depth -= 1,
+            c if c == separator && depth == 0 => {
+                parts.push(&s[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+        .into_iter()
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+/// Removes the type arguments from a name like `List<T>.Enumerator`, keeping compiler generated
+/// names like `<>c`
+fn strip_generic_args(name: &str) -> String {
+    let mut stripped = String::new();
+    let mut depth = 0;
+    for c in name.chars() {
+        match c {
+            '<' if depth > 0 || !(stripped.is_empty() || stripped.ends_with('.')) => depth += 1,
+            '>' if depth > 0 => depth -= 1,
+            _ if depth > 0 => {}
+            _ => stripped.push(c),
+        }
+    }
+    stripped
+}
+
+/// Splits leading modifiers off the tokens of a declaration
+fn split_modifiers<'a, 'b>(tokens: &'b [&'a str]) -> (Vec<String>, &'b [&'a str]) {
+    let count = tokens
+        .iter()
+        .take_while(|token| MODIFIERS.contains(token))
+        .count();
+    let modifiers = tokens[..count].iter().map(|m| m.to_string()).collect();
+    (modifiers, &tokens[count..])
+}
+
+/// Parses `where T : class, IComparable<T> where U : new()` into the constraints of each parameter
+fn parse_constraints(s: &str) -> HashMap<String, Vec<String>> {
+    let mut constraints = HashMap::new();
+    let tokens = split_top_level(s, ' ');
+    let clauses = tokens
+        .split(|token| *token == "where")
+        .filter(|clause| !clause.is_empty());
+    for clause in clauses {
+        if let [param, ":", rest @ ..] = clause {
+            let list = split_top_level(&rest.join(" "), ',')
+                .into_iter()
+                .map(str::to_owned)
+                .collect();
+            constraints.insert(param.to_string(), list);
+        }
+    }
+    constraints
+}
+
+/// `// RVA: 0x1234 Offset: 0x1234 VA: 0x181234 Slot: 4`
+#[derive(Debug, Clone, Copy)]
+struct Address {
+    rva: i32,
+    offset: i32,
+    va: i32,
+    slot: i32,
+}
+
+impl Address {
+    const NONE: Address = Address {
+        rva: -1,
+        offset: -1,
+        va: -1,
+        slot: -1,
+    };
+
+    fn parse(comment: &str) -> Self {
+        let mut address = Self::NONE;
+        let tokens: Vec<&str> = comment.split_whitespace().collect();
+        for pair in tokens.windows(2) {
+            let value = parse_number(pair[1]);
+            match pair[0] {
+                "RVA:" => address.rva = value,
+                "Offset:" => address.offset = value,
+                "VA:" => address.va = value,
+                "Slot:" => address.slot = value,
+                _ => {}
+            }
+        }
+        address
+    }
+}
+
+/// Parses a hexadecimal or decimal number, which is -1 if it is missing or doesn't fit
+fn parse_number(s: &str) -> i32 {
+    let value = match s.strip_prefix("0x") {
+        Some(hex) => i64::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    };
+    value.and_then(|v| i32::try_from(v).ok()).unwrap_or(-1)
+}
+
+fn parse_attribute(line: &str) -> Option<Attribute> {
+    let (attribute, comment) = match line.split_once("//") {
+        Some((attribute, comment)) => (attribute, comment),
+        None => (line, ""),
+    };
+    let name = attribute
+        .trim()
+        .strip_prefix('[')?
+        .split(&['(', ']'][..])
+        .next()?
+        .trim();
+    let address = Address::parse(comment);
+    let name = if name.ends_with("Attribute") {
+        name.to_owned()
+    } else {
+        format!("{}Attribute", name)
+    };
+    Some(Attribute {
+        name,
+        rva: address.rva.max(0),
+        offset: address.offset.max(0),
+        va: address.va.max(0),
+    })
+}
+
+#[derive(Debug)]
+struct ParsedField {
+    attributes: Vec<Attribute>,
+    modifiers: Vec<String>,
+    field_type: TypeExpr,
+    name: String,
+    offset: i32,
+    constant: Option<String>,
+}
+
+#[derive(Debug)]
+struct ParsedProperty {
+    attributes: Vec<Attribute>,
+    modifiers: Vec<String>,
+    property_type: TypeExpr,
+    name: String,
+    get_method: bool,
+    set_method: bool,
+}
+
+#[derive(Debug)]
+struct ParsedParameter {
+    modifier: ParameterModifier,
+    parameter_type: TypeExpr,
+    name: String,
+}
+
+#[derive(Debug)]
+struct ParsedMethod {
+    attributes: Vec<Attribute>,
+    /// Missing if `dump.cs` was written without addresses
+    address: Option<Address>,
+    modifiers: Vec<String>,
+    return_type: TypeExpr,
+    name: String,
+    generics: Vec<String>,
+    constraints: HashMap<String, Vec<String>>,
+    parameters: Vec<ParsedParameter>,
+}
+
+#[derive(Debug)]
+struct ParsedType {
+    /// Namespace given by `dump.cs`, which is empty for nested types
+    namespace: String,
+    /// Name of the type with the names of its declaring types, like `List.Enumerator`
+    path: String,
+    generics: Vec<String>,
+    constraints: HashMap<String, Vec<String>>,
+    kind: TypeEnum,
+    modifiers: Vec<String>,
+    bases: Vec<TypeExpr>,
+    type_def_index: i32,
+    attributes: Vec<Attribute>,
+    fields: Vec<ParsedField>,
+    properties: Vec<ParsedProperty>,
+    methods: Vec<ParsedMethod>,
+}
+
+/// `public class List<T> : IList<T>, IList // TypeDefIndex: 1234`
+fn parse_type(line: &str, namespace: &str, attributes: Vec<Attribute>) -> Option<ParsedType> {
+    let (declaration, index) = line.split_once("// TypeDefIndex:")?;
+    let tokens = split_top_level(declaration, ' ');
+    let kind_position = tokens
+        .iter()
+        .position(|token| matches!(*token, "class" | "struct" | "enum" | "interface"))?;
+    let kind = match tokens[kind_position] {
+        "class" => TypeEnum::Class,
+        "struct" => TypeEnum::Struct,
+        "enum" => TypeEnum::Enum,
+        _ => TypeEnum::Interface,
+    };
+    let modifiers = tokens[..kind_position]
+        .iter()
+        .map(|m| m.to_string())
+        .collect();
+    let name = TypeExpr::parse(tokens.get(kind_position + 1)?)?;
+    let generics = name.args.iter().map(|arg| arg.path.clone()).collect();
+
+    let rest = &tokens[kind_position + 2..];
+    let where_position = rest
+        .iter()
+        .position(|token| *token == "where")
+        .unwrap_or(rest.len());
+    let bases = match rest[..where_position] {
+        [":", ref bases @ ..] => split_top_level(&bases.join(" "), ',')
+            .into_iter()
+            .map(TypeExpr::parse)
+            .collect::<Option<Vec<_>>>()?,
+        _ => Vec::new(),
+    };
+    Some(ParsedType {
+        namespace: namespace.to_owned(),
+        path: name.path,
+        generics,
+        constraints: parse_constraints(&rest[where_position..].join(" ")),
+        kind,
+        modifiers,
+        bases,
+        type_def_index: index.trim().parse().ok()?,
+        attributes,
+        fields: Vec::new(),
+        properties: Vec::new(),
+        methods: Vec::new(),
+    })
+}
+
+/// `private T[] _items; // 0x10` or `public const int Max = 4;`
+fn parse_field(line: &str, attributes: Vec<Attribute>) -> Option<ParsedField> {
+    let (declaration, offset) = match line.rsplit_once("; // ") {
+        Some((declaration, offset)) if offset.starts_with("0x") => {
+            (declaration, parse_number(offset))
+        }
+        _ => (line.strip_suffix(';')?, 0),
+    };
+    let (declaration, constant) = match declaration.split_once(" = ") {
+        Some((declaration, constant)) => {
+            let constant = match constant.trim() {
+                "True" => "true",
+                "False" => "false",
+                constant => constant,
+            };
+            (declaration, Some(constant.to_owned()))
+        }
+        None => (declaration, None),
+    };
+    let tokens = split_top_level(declaration, ' ');
+    let (modifiers, rest) = split_modifiers(&tokens);
+    let (name, field_type) = rest.split_last()?;
+    Some(ParsedField {
+        attributes,
+        modifiers,
+        field_type: TypeExpr::parse(&field_type.join(" "))?,
+        name: name.to_string(),
+        offset,
+        constant,
+    })
+}
+
+/// `public int Count { get; set; }`
+fn parse_property(line: &str, attributes: Vec<Attribute>) -> Option<ParsedProperty> {
+    let (declaration, accessors) = line.split_once(" { ")?;
+    let tokens = split_top_level(declaration, ' ');
+    let (modifiers, rest) = split_modifiers(&tokens);
+    let (name, property_type) = rest.split_last()?;
+    // Indexers are called `Item` in metadata
+    let name = if name.starts_with("this[") {
+        "Item"
+    } else {
+        name
+    };
+    Some(ParsedProperty {
+        attributes,
+        modifiers,
+        property_type: TypeExpr::parse(&property_type.join(" "))?,
+        name: name.to_owned(),
+        get_method: accessors.contains("get;"),
+        set_method: accessors.contains("set;"),
+    })
+}
+
+/// `public static T Find<T>(string name, bool includeInactive = false) { }`
+fn parse_method(
+    line: &str,
+    attributes: Vec<Attribute>,
+    address: Option<Address>,
+) -> Option<ParsedMethod> {
+    let line = line
+        .strip_suffix("{ }")
+        .or_else(|| line.strip_suffix(';'))?
+        .trim();
+
+    let mut depth = 0;
+    let mut open = None;
+    for (i, c) in line.char_indices() {
+        match c {
+            '<' | '[' => depth += 1,
+            '>' | ']' => depth -= 1,
+            '(' if depth == 0 => {
+                open = Some(i);
+                break;
+            }
+            _ => {}
+        }
+    }
+    let open = open?;
+    let mut depth = 0;
+    let close = line[open..].char_indices().find_map(|(i, c)| {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        (depth == 0).then(|| open + i)
+    })?;
+
+    let tokens = split_top_level(&line[..open], ' ');
+    let (modifiers, rest) = split_modifiers(&tokens);
+    let (name, return_type) = rest.split_last()?;
+    let return_type = match return_type {
+        ["ref", "readonly", return_type @ ..] | ["ref", return_type @ ..] => return_type,
+        return_type => return_type,
+    };
+    let return_type = match return_type.join(" ").as_str() {
+        "" => TypeExpr::parse("void")?,
+        return_type => TypeExpr::parse(return_type)?,
+    };
+    let (name, generics) = match name.strip_suffix('>').and_then(|n| n.split_once('<')) {
+        Some((name, generics)) if !name.is_empty() && !name.ends_with('.') => (
+            name.to_owned(),
+            split_top_level(generics, ',')
+                .into_iter()
+                .map(str::to_owned)
+                .collect(),
+        ),
+        _ => (name.to_string(), Vec::new()),
+    };
+
+    let mut parameters = Vec::new();
+    for parameter in split_top_level(&line[open + 1..close], ',') {
+        let declaration = match parameter.split_once(" = ") {
+            Some((declaration, _default)) => declaration,
+            None => parameter,
+        };
+        let tokens: Vec<&str> = split_top_level(declaration, ' ')
+            .into_iter()
+            .filter(|token| !token.starts_with('['))
+            .collect();
+        let (modifier, rest) = match tokens.split_first()? {
+            (&"ref", rest) => (ParameterModifier::Ref, rest),
+            (&"out", rest) => (ParameterModifier::Out, rest),
+            (&"in", rest) => (ParameterModifier::In, rest),
+            (&"params", rest) => (ParameterModifier::Params, rest),
+            _ => (ParameterModifier::None, &tokens[..]),
+        };
+        let (name, parameter_type) = rest.split_last()?;
+        parameters.push(ParsedParameter {
+            modifier,
+            parameter_type: TypeExpr::parse(&parameter_type.join(" "))?,
+            name: name.to_string(),
+        });
+    }
+
+    Some(ParsedMethod {
+        attributes,
+        address,
+        modifiers,
+        return_type,
+        name,
+        generics,
+        constraints: parse_constraints(&line[close + 1..]),
+        parameters,
+    })
+}
+
+/// Lines of `dump.cs` which could not be parsed
+#[derive(Default)]
+struct Unparsed {
+    count: usize,
+    first: Option<(usize, String)>,
+}
+
+impl Unparsed {
+    fn add(&mut self, number: usize, line: &str) {
+        self.count += 1;
+        self.first.get_or_insert_with(|| (number, line.to_owned()));
+    }
+}
+
+fn parse_dump_cs(source: &str) -> (Vec<ParsedType>, Unparsed) {
+    let mut types = Vec::new();
+    let mut unparsed = Unparsed::default();
+    let mut namespace = String::new();
+    let mut attributes = Vec::new();
+    let mut address = None;
+    let mut current: Option<ParsedType> = None;
+    let mut in_comment = false;
+
+    for (i, line) in source.lines().enumerate() {
+        let line = line.trim();
+        if in_comment {
+            in_comment = !line.contains("*/");
+            continue;
+        }
+        if line.starts_with("/*") {
+            in_comment = !line.contains("*/");
+            continue;
+        }
+        if let Some(name) = line.strip_prefix("// Namespace:") {
+            namespace = name.trim().to_owned();
+            continue;
+        }
+        if line.starts_with("// RVA:") {
+            address = Some(Address::parse(line));
+            continue;
+        }
+        if line.is_empty() || line.starts_with("//") || line == "{" {
+            continue;
+        }
+        if line.starts_with('[') {
+            match parse_attribute(line) {
+                Some(attribute) => attributes.push(attribute),
+                None => unparsed.add(i + 1, line),
+            }
+            continue;
+        }
+        if line == "}" || line == "{}" {
+            types.extend(current.take());
+            continue;
+        }
+
+        let attributes = std::mem::take(&mut attributes);
+        let ty = match &mut current {
+            Some(ty) => ty,
+            None => {
+                match parse_type(line, &namespace, attributes) {
+                    Some(ty) => current = Some(ty),
+                    None => unparsed.add(i + 1, line),
+                }
+                continue;
+            }
+        };
+        let parsed = if line.starts_with("where ") {
+            // Constraints of the type on their own line
+            ty.constraints.extend(parse_constraints(line));
+            Some(())
+        } else if line.ends_with("{ }") || line.ends_with(");") {
+            parse_method(line, attributes, address.take()).map(|method| ty.methods.push(method))
+        } else if line.contains("{ get;") || line.contains("{ set;") {
+            parse_property(line, attributes).map(|property| ty.properties.push(property))
+        } else {
+            parse_field(line, attributes).map(|field| {
+                // Events only have their accessor methods in il2cpp
+                if !field.modifiers.iter().any(|m| m == "event") {
+                    ty.fields.push(field);
+                }
+            })
+        };
+        if parsed.is_none() {
+            unparsed.add(i + 1, line);
+        }
+    }
+    types.extend(current);
+    (types, unparsed)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Script {
+    script_method: Vec<ScriptMethod>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ScriptMethod {
+    /// Relative to the start of the library
+    address: u64,
+    /// `Namespace.Type$$Method`
+    name: String,
+}
+
+/// Gives methods without an address the ones of `script.json`. Methods are matched by their name
+/// and their order among the overloads, so only names with as many methods in `script.json` as in
+/// `dump.cs` are taken, which leaves out generic instances.
+fn apply_script(types: &mut [ParsedType], script: &Script) -> usize {
+    let mut addresses: HashMap<String, Vec<u64>> = HashMap::new();
+    for method in &script.script_method {
+        addresses
+            .entry(strip_generic_args(&method.name))
+            .or_default()
+            .push(method.address);
+    }
+
+    let mut applied = 0;
+    for ty in types {
+        let type_name = match ty.namespace.as_str() {
+            "" => ty.path.clone(),
+            namespace => format!("{}.{}", namespace, ty.path),
+        };
+        let mut overloads: HashMap<String, Vec<&mut ParsedMethod>> = HashMap::new();
+        for method in ty.methods.iter_mut().filter(|m| m.address.is_none()) {
+            let key = format!("{}$${}", type_name, method.name);
+            overloads.entry(key).or_default().push(method);
+        }
+        for (key, methods) in overloads {
+            let addresses = match addresses.get(&key) {
+                Some(addresses) if addresses.len() == methods.len() => addresses,
+                _ => continue,
+            };
+            for (method, &address) in methods.into_iter().zip(addresses) {
+                let rva = i32::try_from(address).unwrap_or(-1);
+                // The libraries of il2cpp games are mapped as they are stored, so the offset in
+                // the file is the same as the address
+                method.address = Some(Address {
+                    rva,
+                    offset: rva,
+                    ..Address::NONE
+                });
+                applied += 1;
+            }
+        }
+    }
+    applied
+}
+
+fn type_ref(namespace: String, name: String, type_id: i32, generics: Vec<TypeRef>) -> TypeRef {
+    TypeRef {
+        namespace,
+        name,
+        type_id,
+        generics,
+        is_generic_parameter: false,
+        is_array: false,
+        is_pointer: false,
+        generic_parameter_constraints: Vec::new(),
+        special_constraints: Vec::new(),
+    }
+}
+
+fn generic_parameter(name: &str) -> TypeRef {
+    TypeRef {
+        is_generic_parameter: true,
+        ..type_ref(String::new(), name.to_owned(), -1, Vec::new())
+    }
+}
+
+/// The generic parameters which type names of a type or method are resolved against
+struct Scope<'a> {
+    /// Id of the type the names are used in
+    id: usize,
+    generics: Vec<&'a str>,
+}
+
+/// Builds the types of the dump from the parsed ones, whose type ids are their indices
+struct Importer<'a> {
+    parsed: &'a [ParsedType],
+    /// Types by their path and number of generic parameters
+    by_path: HashMap<(&'a str, usize), Vec<usize>>,
+    declaring_types: Vec<Option<usize>>,
+    /// Array and pointer types which can't be written as a flagged reference to their element
+    /// type, which are added after the parsed types
+    composites: Vec<TypeData>,
+    composite_ids: HashMap<String, i32>,
+    unresolved: BTreeSet<String>,
+}
+
+impl<'a> Importer<'a> {
+    fn new(parsed: &'a [ParsedType]) -> Self {
+        let mut by_path: HashMap<(&str, usize), Vec<usize>> = HashMap::new();
+        for (id, ty) in parsed.iter().enumerate() {
+            by_path
+                .entry((&ty.path, ty.generics.len()))
+                .or_default()
+                .push(id);
+        }
+
+        // Declaring types are named without their type arguments, so the closest preceding type
+        // of that name is taken, as types are nested in the order they are declared in
+        let declaring_types = parsed
+            .iter()
+            .map(|ty| {
+                let (declaring_path, _) = ty.path.rsplit_once('.')?;
+                let candidates = (0..=ty.generics.len())
+                    .filter_map(|arity| by_path.get(&(declaring_path, arity)))
+                    .flatten()
+                    .copied();
+                candidates.min_by_key(|&id| {
+                    let distance = ty.type_def_index - parsed[id].type_def_index;
+                    (distance <= 0, distance.abs())
+                })
+            })
+            .collect();
+
+        Self {
+            parsed,
+            by_path,
+            declaring_types,
+            composites: Vec::new(),
+            composite_ids: HashMap::new(),
+            unresolved: BTreeSet::new(),
+        }
+    }
+
+    /// Namespace of the outermost declaring type
+    fn outer_namespace(&self, mut id: usize) -> &'a str {
+        let mut depth = 0;
+        while let Some(declaring_type) = self.declaring_types[id] {
+            id = declaring_type;
+            depth += 1;
+            if depth > self.parsed.len() {
+                break;
+            }
+        }
+        &self.parsed[id].namespace
+    }
+
+    /// Namespace in the dump, where nested types have none and the global namespace has a name
+    fn namespace(&self, id: usize) -> String {
+        match (self.declaring_types[id], self.parsed[id].namespace.as_str()) {
+            (Some(_), _) => String::new(),
+            (None, "") => "GlobalNamespace".to_owned(),
+            (None, namespace) => namespace.to_owned(),
+        }
+    }
+
+    /// Name in metadata, where the number of generic parameters the type adds to those of its
+    /// declaring type follows a backtick
+    fn name(&self, id: usize) -> String {
+        let ty = &self.parsed[id];
+        let name = match ty.path.rsplit_once('.') {
+            Some((_, name)) => name,
+            None => &ty.path,
+        };
+        let inherited = self.declaring_types[id].map_or(0, |declaring_type| {
+            self.parsed[declaring_type].generics.len()
+        });
+        match ty.generics.len().saturating_sub(inherited) {
+            0 => name.to_owned(),
+            count => format!("{}`{}", name, count),
+        }
+    }
+
+    fn type_ref(&self, id: usize, generics: Vec<TypeRef>) -> TypeRef {
+        type_ref(self.namespace(id), self.name(id), id as i32, generics)
+    }
+
+    fn system_type(&self, name: &str, arity: usize) -> Option<usize> {
+        self.by_path
+            .get(&(name, arity))?
+            .iter()
+            .copied()
+            .find(|&id| self.parsed[id].namespace == "System")
+    }
+
+    /// Finds the type a name refers to, preferring those in namespaces closer to the one the
+    /// name is used in
+    fn find(&self, path: &str, arity: usize, scope: &Scope<'_>) -> Option<usize> {
+        if let Some((_, name)) = KEYWORD_TYPES.iter().find(|(keyword, _)| *keyword == path) {
+            return self.system_type(name, 0);
+        }
+        let candidates = self.by_path.get(&(path, arity))?;
+        let namespace: Vec<&str> = self.outer_namespace(scope.id).split('.').collect();
+        // The types of `System` with keywords are written as those, so their name refers to
+        // another type
+        let is_keyword_type = KEYWORD_TYPES.iter().any(|(_, name)| *name == path);
+        candidates.iter().copied().max_by_key(|&id| {
+            let candidate = self.outer_namespace(id);
+            let shared = candidate
+                .split('.')
+                .zip(&namespace)
+                .take_while(|(a, b)| a == *b)
+                .count();
+            let is_shadowed = is_keyword_type && candidate == "System";
+            (
+                !is_shadowed,
+                candidate == namespace.join("."),
+                shared,
+                std::cmp::Reverse(id),
+            )
+        })
+    }
+
+    fn resolve(&mut self, expr: &TypeExpr, scope: &Scope<'_>) -> TypeRef {
+        let (suffix, element) = match expr.suffixes.split_last() {
+            Some((suffix, rest)) => (
+                *suffix,
+                TypeExpr {
+                    suffixes: rest.to_vec(),
+                    ..expr.clone()
+                },
+            ),
+            None => return self.resolve_name(expr, scope),
+        };
+        let element = self.resolve(&element, scope);
+        match suffix {
+            Suffix::Nullable => match self.system_type("Nullable", 1) {
+                Some(id) => self.type_ref(id, vec![element]),
+                None => {
+                    self.unresolved.insert("Nullable".to_owned());
+                    element
+                }
+            },
+            Suffix::Array(1) | Suffix::Pointer if !element.is_array && !element.is_pointer => {
+                TypeRef {
+                    is_array: suffix != Suffix::Pointer,
+                    is_pointer: suffix == Suffix::Pointer,
+                    ..element
+                }
+            }
+            _ => self.composite(element, suffix),
+        }
+    }
+
+    fn resolve_name(&mut self, expr: &TypeExpr, scope: &Scope<'_>) -> TypeRef {
+        if expr.args.is_empty() && scope.generics.contains(&expr.path.as_str()) {
+            return generic_parameter(&expr.path);
+        }
+        let generics: Vec<TypeRef> = expr
+            .args
+            .iter()
+            .map(|arg| self.resolve(arg, scope))
+            .collect();
+        match self.find(&expr.path, generics.len(), scope) {
+            Some(id) => self.type_ref(id, generics),
+            None => {
+                self.unresolved.insert(expr.path.clone());
+                type_ref(String::new(), expr.path.clone(), -1, generics)
+            }
+        }
+    }
+
+    /// An array or pointer type of its own, like the dumper creates for every array and pointer
+    /// type
+    fn composite(&mut self, element: TypeRef, suffix: Suffix) -> TypeRef {
+        let (name_suffix, is_array) = match suffix {
+            Suffix::Array(rank) => (format!("[{}]", ",".repeat(rank - 1)), true),
+            _ => ("*".to_owned(), false),
+        };
+        // Flagged references are named after their element type
+        let mut name = element.name.clone();
+        if element.is_array && !name.ends_with(']') {
+            name.push_str("[]");
+        }
+        if element.is_pointer && !name.ends_with('*') {
+            name.push('*');
+        }
+        name.push_str(&name_suffix);
+        let key = format!("{:?}{}", element, name_suffix);
+        let type_id = match self.composite_ids.get(&key) {
+            Some(&type_id) => type_id,
+            None => {
+                let type_id = (self.parsed.len() + self.composites.len()) as i32;
+                let this = TypeDataThis {
+                    namespace: element.namespace.clone(),
+                    qualified_cpp_name: format!(
+                        "{}::{}",
+                        element.namespace.replace('.', "::"),
+                        name
+                    ),
+                    name: name.clone(),
+                    is_generic_template: false,
+                    is_nested: false,
+                    element_type: Some(element.clone()),
+                    generic_parameter_constraints: Vec::new(),
+                    generics: Vec::new(),
+                    declaring_type: None,
+                };
+                let (type_enum, size) = if is_array {
+                    (TypeEnum::Class, OBJECT_HEADER_SIZE * 2)
+                } else {
+                    (TypeEnum::Struct, POINTER_SIZE)
+                };
+                self.composites.push(empty_type(this, type_enum, -1, size));
+                self.composite_ids.insert(key, type_id);
+                type_id
+            }
+        };
+        TypeRef {
+            is_array,
+            is_pointer: !is_array,
+            ..type_ref(element.namespace, name, type_id, Vec::new())
+        }
+    }
+
+    fn generic_parameters(
+        &mut self,
+        names: &[String],
+        constraints: &HashMap<String, Vec<String>>,
+        scope: &Scope<'_>,
+    ) -> Vec<TypeRef> {
+        names
+            .iter()
+            .map(|name| {
+                let mut param = generic_parameter(name);
+                for constraint in constraints.get(name).into_iter().flatten() {
+                    match constraint.as_str() {
+                        "class" | "struct" | "new()" => {
+                            param.special_constraints.push(constraint.clone())
+                        }
+                        "unmanaged" => param.special_constraints.push("struct".to_owned()),
+                        constraint => match TypeExpr::parse(constraint) {
+                            Some(expr) => {
+                                let constraint = self.resolve(&expr, scope);
+                                param.generic_parameter_constraints.push(constraint);
+                            }
+                            None => {
+                                self.unresolved.insert(constraint.to_owned());
+                            }
+                        },
+                    }
+                }
+                param
+            })
+            .collect()
+    }
+
+    fn field(&mut self, id: usize, field: &ParsedField, scope: &Scope<'_>) -> Field {
+        let mut specifiers = field.modifiers.clone();
+        // Constants are static fields in metadata
+        if let Some(i) = specifiers.iter().position(|s| s == "const") {
+            specifiers.insert(i, "static".to_owned());
+        }
+        let is_instance = !specifiers.iter().any(|s| s == "static");
+        let offset = match self.parsed[id].kind {
+            TypeEnum::Struct | TypeEnum::Enum if is_instance => field.offset + OBJECT_HEADER_SIZE,
+            _ => field.offset,
+        };
+        Field {
+            attributes: field.attributes.clone(),
+            name: field.name.clone(),
+            offset,
+            layout_offset: offset,
+            specifiers,
+            field_type: self.resolve(&field.field_type, scope),
+            constant: field.constant.clone(),
+        }
+    }
+
+    fn method(&mut self, ty: &ParsedType, method: &ParsedMethod, scope: &Scope<'_>) -> Method {
+        let mut generics = scope.generics.clone();
+        generics.extend(method.generics.iter().map(String::as_str));
+        let scope = Scope {
+            id: scope.id,
+            generics,
+        };
+        let generic_parameters =
+            self.generic_parameters(&method.generics, &method.constraints, &scope);
+        let parameters = method
+            .parameters
+            .iter()
+            .map(|parameter| Parameter {
+                parameter_type: self.resolve(&parameter.parameter_type, &scope),
+                name: parameter.name.clone(),
+                modifier: parameter.modifier.clone(),
+            })
+            .collect();
+        let is_accessor = ty.properties.iter().any(|property| {
+            method.name == format!("get_{}", property.name)
+                || method.name == format!("set_{}", property.name)
+        });
+        let has = |modifier: &str| method.modifiers.iter().any(|m| m == modifier);
+        let address = method.address.unwrap_or(Address::NONE);
+        Method {
+            attributes: method.attributes.clone(),
+            generic: !method.generics.is_empty(),
+            generic_parameters,
+            hides_base: has("new"),
+            il2cpp_name: method.name.clone(),
+            implemented_from: None,
+            is_special_name: is_accessor
+                || method.name.starts_with('.')
+                || method.name.starts_with("op_"),
+            is_virtual: has("virtual") || has("override") || has("abstract"),
+            name: method.name.clone(),
+            offset: address.offset,
+            parameters,
+            return_type: self.resolve(&method.return_type, &scope),
+            rva: address.rva,
+            slot: address.slot,
+            specifiers: method.modifiers.clone(),
+            va: address.va,
+        }
+    }
+
+    fn build(&mut self, id: usize) -> TypeData {
+        let parsed = self.parsed;
+        let ty = &parsed[id];
+        let scope = Scope {
+            id,
+            generics: ty.generics.iter().map(String::as_str).collect(),
+        };
+
+        let mut parent = None;
+        let mut implementing_interfaces = Vec::new();
+        for (i, base) in ty.bases.iter().enumerate() {
+            let base = self.resolve(base, &scope);
+            let is_class = parsed
+                .get(base.type_id as usize)
+                .is_some_and(|base| !matches!(base.kind, TypeEnum::Interface));
+            if i == 0 && is_class && matches!(ty.kind, TypeEnum::Class) {
+                parent = Some(base);
+            } else {
+                implementing_interfaces.push(base);
+            }
+        }
+        // `dump.cs` leaves out the base classes all types of a kind have
+        let implicit_parent = match ty.kind {
+            TypeEnum::Class if ty.namespace != "System" || ty.path != "Object" => Some("Object"),
+            TypeEnum::Struct => Some("ValueType"),
+            TypeEnum::Enum => Some("Enum"),
+            _ => None,
+        };
+        if parent.is_none() {
+            if let Some(name) = implicit_parent {
+                parent = self
+                    .system_type(name, 0)
+                    .map(|id| self.type_ref(id, Vec::new()));
+            }
+        }
+
+        let namespace = self.namespace(id);
+        let name = self.name(id);
+        let this = TypeDataThis {
+            qualified_cpp_name: format!("{}::{}", namespace.replace('.', "::"), name),
+            namespace,
+            name,
+            is_generic_template: !ty.generics.is_empty(),
+            is_nested: self.declaring_types[id].is_some(),
+            element_type: None,
+            generic_parameter_constraints: Vec::new(),
+            generics: self.generic_parameters(&ty.generics, &ty.constraints, &scope),
+            declaring_type: self.declaring_types[id].map(|id| self.type_ref(id, Vec::new())),
+        };
+        let mut data = empty_type(this, ty.kind.clone(), ty.type_def_index, 0);
+        data.attributes = ty.attributes.clone();
+        data.specifiers = ty.modifiers.clone();
+        data.layout = match ty.kind {
+            TypeEnum::Struct | TypeEnum::Enum => LayoutKind::Sequential,
+            _ => LayoutKind::Auto,
+        };
+        data.parent = parent;
+        data.implementing_interfaces = implementing_interfaces;
+        for field in &ty.fields {
+            let field = self.field(id, field, &scope);
+            if field.specifiers.iter().any(|s| s == "static") {
+                data.static_fields.push(field);
+            } else {
+                data.instance_fields.push(field);
+            }
+        }
+        for property in &ty.properties {
+            let property_type = self.resolve(&property.property_type, &scope);
+            data.properties.push(Property {
+                attributes: property.attributes.clone(),
+                specifiers: property.modifiers.clone(),
+                get_method: property.get_method,
+                set_method: property.set_method,
+                name: property.name.clone(),
+                property_type,
+            });
+        }
+        for method in &ty.methods {
+            let method = self.method(ty, method, &scope);
+            data.methods.push(method);
+        }
+        data
+    }
+}
+
+fn empty_type(this: TypeDataThis, type_enum: TypeEnum, type_def_index: i32, size: i32) -> TypeData {
+    TypeData {
+        this,
+        attributes: Vec::new(),
+        implementing_interfaces: Vec::new(),
+        instance_fields: Vec::new(),
+        layout: LayoutKind::Auto,
+        methods: Vec::new(),
+        nested_types: Vec::new(),
+        parent: None,
+        properties: Vec::new(),
+        specifiers: Vec::new(),
+        static_fields: Vec::new(),
+        type_enum,
+        type_def_index,
+        size,
+    }
+}
+
+/// Estimates the sizes of types from the offsets and types of their fields, in the way dumps of
+/// the dumper give them: including the object header for value types too
+struct SizeEstimator<'a> {
+    dump: &'a DllData,
+    sizes: Vec<Option<i32>>,
+}
+
+impl SizeEstimator<'_> {
+    /// Size of a value of the type when stored in a field, which is a pointer for reference types
+    fn field_size(&mut self, type_ref: &TypeRef) -> i32 {
+        if type_ref.is_array || type_ref.is_pointer {
+            return POINTER_SIZE;
+        }
+        match self.dump.get(type_ref) {
+            Some(ty) if matches!(ty.type_enum, TypeEnum::Struct | TypeEnum::Enum) => {
+                self.size(type_ref.type_id as usize) - OBJECT_HEADER_SIZE
+            }
+            _ => POINTER_SIZE,
+        }
+    }
+
+    fn size(&mut self, id: usize) -> i32 {
+        if let Some(size) = self.sizes[id] {
+            return size;
+        }
+        let ty = &self.dump.types[id];
+        if ty.this.element_type.is_some() {
+            return ty.size;
+        }
+        // Breaks cycles of value types containing themselves, like primitives
+        self.sizes[id] = Some(OBJECT_HEADER_SIZE + POINTER_SIZE);
+
+        let primitive = PRIMITIVE_SIZES
+            .iter()
+            .find(|(name, _)| ty.this.namespace == "System" && ty.this.name == *name);
+        let size = if let Some((_, size)) = primitive {
+            OBJECT_HEADER_SIZE + size
+        } else {
+            let mut end = match &ty.parent {
+                Some(parent) if self.dump.get(parent).is_some() => {
+                    self.size(parent.type_id as usize)
+                }
+                _ => OBJECT_HEADER_SIZE,
+            };
+            let mut alignment = 1;
+            for field in &ty.instance_fields {
+                let size = self.field_size(&field.field_type);
+                end = end.max(field.offset + size);
+                let field_alignment = (size.clamp(1, POINTER_SIZE) as u32).next_power_of_two();
+                alignment = alignment.max(field_alignment as i32);
+            }
+            match ty.type_enum {
+                TypeEnum::Struct | TypeEnum::Enum => {
+                    // Empty structs still take up a byte
+                    let payload = (end - OBJECT_HEADER_SIZE).max(1);
+                    OBJECT_HEADER_SIZE + round_up(payload, alignment)
+                }
+                _ => round_up(end, POINTER_SIZE),
+            }
+        };
+        self.sizes[id] = Some(size);
+        size
+    }
+}
+
+fn round_up(value: i32, alignment: i32) -> i32 {
+    (value + alignment - 1) / alignment * alignment
+}
+
+impl DllData {
+    /// Imports the `dump.cs` and, if it exists, the `script.json` which Il2CppDumper wrote to
+    /// `dir`
+    pub fn from_il2cpp_dumper(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref();
+        let dump_path = dir.join("dump.cs");
+        let source = fs::read_to_string(&dump_path)
+            .with_context(|| format!("Failed to read {}", dump_path.display()))?;
+        let (mut parsed, unparsed) = parse_dump_cs(&source);
+        if parsed.is_empty() {
+            bail!("{} does not contain any types", dump_path.display());
+        }
+        if let Some((line_number, line)) = unparsed.first {
+            eprintln!(
+                "Warning: skipped {} lines of {} which could not be parsed, like line {}: `{}`",
+                unparsed.count,
+                dump_path.display(),
+                line_number,
+                line
+            );
+        }
+
+        let script_path = dir.join("script.json");
+        let has_addresses = parsed
+            .iter()
+            .flat_map(|ty| &ty.methods)
+            .all(|method| method.address.is_some());
+        if !has_addresses && script_path.exists() {
+            let script = fs::read_to_string(&script_path)
+                .with_context(|| format!("Failed to read {}", script_path.display()))?;
+            let script: Script = serde_json::from_str(&script)
+                .with_context(|| format!("Failed to parse {}", script_path.display()))?;
+            let applied = apply_script(&mut parsed, &script);
+            eprintln!(
+                "Took the addresses of {} methods from {}",
+                applied,
+                script_path.display()
+            );
+        }
+
+        let mut importer = Importer::new(&parsed);
+        let mut types: Vec<TypeData> = (0..parsed.len()).map(|id| importer.build(id)).collect();
+        types.append(&mut importer.composites);
+        if let Some(first) = importer.unresolved.iter().next() {
+            eprintln!(
+                "Warning: {} type names could not be resolved, like `{}`",
+                importer.unresolved.len(),
+                first
+            );
+        }
+
+        let mut dump = DllData { types };
+        let mut estimator = SizeEstimator {
+            sizes: vec![None; dump.types.len()],
+            dump: &dump,
+        };
+        let sizes: Vec<i32> = (0..dump.types.len()).map(|id| estimator.size(id)).collect();
+        for (ty, size) in dump.types.iter_mut().zip(sizes) {
+            ty.size = size;
+        }
+        Ok(dump)
+    }
+}
@@ -0,0 +1,261 @@
+//! Loading of dumps, either from the JSON written by the dumper or from its bincode cache. The
+//! cache starts with a header holding the version of its format, so caches written by other
+//! versions of the generator are recreated instead of being misread.
+
+use crate::config::Input;
+use crate::data::{DllData, LayoutKind, ParameterModifier, TypeData, TypeEnum};
+use crate::schema::{self, SchemaVersion};
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use serde_path_to_error::Segment;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+/// Start of dump caches
+const CACHE_MAGIC: &[u8; 8] = b"il2cppcg";
+
+/// Version of the format of dump caches, which has to be increased whenever `DllData` changes
+const CACHE_VERSION: u32 = 1;
+
+/// The top level of a JSON dump
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct JsonDump {
+    /// Version of the schema, which the dumper does not write yet
+    #[serde(default)]
+    version: Option<u32>,
+    types: Vec<TypeData>,
+}
+
+/// Name of a type as it appears in the dump, for the types of a dump which can't be parsed
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TypeName {
+    namespace: String,
+    name: String,
+    declaring_type: Option<Box<TypeName>>,
+}
+
+impl TypeName {
+    fn full_name(&self) -> String {
+        match &self.declaring_type {
+            Some(declaring_type) => format!("{}/{}", declaring_type.full_name(), self.name),
+            None if self.namespace.is_empty() => self.name.clone(),
+            None => format!("{}.{}", self.namespace, self.name),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TypeNames {
+    types: Vec<TypeNameEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TypeNameEntry {
+    this: TypeName,
+}
+
+/// Full name of the type at `index` in the JSON dump at `path`. The dump is read again with only
+/// the names of the types, which fails if the names themselves are broken.
+fn type_name_at(path: &Path, index: usize) -> Option<String> {
+    let json = File::open(path).ok()?;
+    let names: TypeNames = serde_json::from_reader(BufReader::new(json)).ok()?;
+    names.types.get(index).map(|entry| entry.this.full_name())
+}
+
+/// Path of an ignored field with the indices of sequences left out, like `Types[].This.Token`,
+/// so that a field every type has is only reported once
+fn field_pattern(path: &serde_ignored::Path<'_>) -> String {
+    match path {
+        serde_ignored::Path::Root => String::new(),
+        serde_ignored::Path::Seq { parent, .. } => field_pattern(parent) + "[]",
+        serde_ignored::Path::Map { parent, key } => {
+            let parent = field_pattern(parent);
+            if parent.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", parent, key)
+            }
+        }
+        serde_ignored::Path::Some { parent }
+        | serde_ignored::Path::NewtypeStruct { parent }
+        | serde_ignored::Path::NewtypeVariant { parent } => field_pattern(parent),
+    }
+}
+
+/// Warns about the values of the dump the generator does not know, which were parsed as
+/// `Unknown`
+fn warn_unknown_variants(dll_data: &DllData) {
+    let types = &dll_data.types;
+    let unknown_kinds = types
+        .iter()
+        .filter(|ty| matches!(ty.type_enum, TypeEnum::Unknown))
+        .count();
+    let unknown_layouts = types
+        .iter()
+        .filter(|ty| matches!(ty.layout, LayoutKind::Unknown))
+        .count();
+    let unknown_modifiers = types
+        .iter()
+        .flat_map(|ty| &ty.methods)
+        .flat_map(|method| &method.parameters)
+        .filter(|parameter| matches!(parameter.modifier, ParameterModifier::Unknown))
+        .count();
+    for (count, what) in [
+        (
+            unknown_kinds,
+            "types are of an unknown kind, they can't be generated",
+        ),
+        (unknown_layouts, "types have an unknown layout kind"),
+        (unknown_modifiers, "parameters have an unknown modifier"),
+    ]
+    .iter()
+    {
+        if *count > 0 {
+            eprintln!("Warning: {} {}", count, what);
+        }
+    }
+}
+
+impl DllData {
+    /// Reads a JSON dump created by the dumper, converted to the latest schema version
+    pub fn from_json_file(path: impl AsRef<Path>) -> Result<Self> {
+        Self::read_json(path).map(|(dll_data, _)| dll_data)
+    }
+
+    /// Reads a JSON dump created by the dumper, converted to the latest schema version, and the
+    /// version it was written with. Fields the generator does not know are ignored and values it
+    /// does not know are read as `Unknown`, with a warning for both. Errors name the path to the
+    /// value that could not be parsed, like `Types[4812].Methods[3].Name`, and the type it
+    /// belongs to.
+    pub fn read_json(path: impl AsRef<Path>) -> Result<(Self, SchemaVersion)> {
+        let path = path.as_ref();
+        let json = File::open(path)
+            .with_context(|| format!("Failed to open JSON dump {}", path.display()))?;
+        let mut deserializer = serde_json::Deserializer::from_reader(BufReader::new(json));
+        let mut track = serde_path_to_error::Track::new();
+        let mut ignored = BTreeMap::new();
+        let result = serde_ignored::deserialize(
+            serde_path_to_error::Deserializer::new(&mut deserializer, &mut track),
+            |path| *ignored.entry(field_pattern(&path)).or_insert(0) += 1,
+        )
+        .and_then(|dump| deserializer.end().map(|()| dump));
+        let dump: JsonDump = match result {
+            Ok(dump) => dump,
+            Err(error) => {
+                let error_path = track.path();
+                let type_name = match error_path.iter().nth(1) {
+                    Some(Segment::Seq { index }) => type_name_at(path, *index),
+                    _ => None,
+                };
+                let location = match type_name {
+                    Some(type_name) => format!("`{}` (type `{}`)", error_path, type_name),
+                    None => format!("`{}`", error_path),
+                };
+                return Err(error).with_context(|| {
+                    format!(
+                        "Failed to parse JSON dump {} at {}",
+                        path.display(),
+                        location
+                    )
+                });
+            }
+        };
+
+        for (field, count) in ignored {
+            eprintln!(
+                "Warning: ignored {} values of unknown field `{}`",
+                count, field
+            );
+        }
+        let mut dll_data = Self { types: dump.types };
+        warn_unknown_variants(&dll_data);
+
+        let version = match dump.version {
+            Some(number) => SchemaVersion::from_number(number).unwrap_or_else(|| {
+                eprintln!(
+                    "Warning: schema version {} is not supported, reading the dump as version {}",
+                    number,
+                    SchemaVersion::LATEST.number()
+                );
+                SchemaVersion::LATEST
+            }),
+            None => SchemaVersion::V1,
+        };
+        schema::upgrade(&mut dll_data, version);
+        Ok((dll_data, version))
+    }
+
+    /// Writes a cache of the dump, which `from_cache_file` reads
+    pub fn write_cache_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let cache = File::create(path)
+            .with_context(|| format!("Failed to create JSON dump cache {}", path.display()))?;
+        let mut cache = BufWriter::new(cache);
+        cache.write_all(CACHE_MAGIC)?;
+        cache.write_all(&CACHE_VERSION.to_le_bytes())?;
+        bincode::serialize_into(&mut cache, self)
+            .with_context(|| format!("Failed to serialize JSON dump cache {}", path.display()))?;
+        cache.flush()?;
+        Ok(())
+    }
+
+    /// Reads a dump cache created by `load`
+    pub fn from_cache_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let cache = File::open(path)
+            .with_context(|| format!("Failed to open JSON dump cache {}", path.display()))?;
+        let mut cache = BufReader::new(cache);
+        if !read_cache_header(&mut cache) {
+            bail!(
+                "JSON dump cache {} was written by another version of the generator",
+                path.display()
+            );
+        }
+        bincode::deserialize_from(cache)
+            .with_context(|| format!("Failed to read JSON dump cache {}", path.display()))
+    }
+
+    /// Reads the dump configured by `input`. Parsing the JSON is slow, so the dump is read from
+    /// the cache if it exists, and the cache is created otherwise. Output of Il2CppDumper is
+    /// imported without a cache.
+    pub fn load(input: &Input) -> Result<Self> {
+        if let Some(dir) = &input.il2cpp_dumper {
+            return Self::from_il2cpp_dumper(dir);
+        }
+        if input.cache.exists() {
+            let cache = File::open(&input.cache).context("Failed to open JSON dump cache")?;
+            let mut cache = BufReader::new(cache);
+            if !read_cache_header(&mut cache) {
+                eprintln!("Codegen data cache is outdated, recreating it");
+            } else {
+                match bincode::deserialize_from(cache) {
+                    Ok(dll_data) => return Ok(dll_data),
+                    Err(error) => {
+                        eprintln!("Codegen data cache is corrupt ({}), recreating it", error)
+                    }
+                }
+            }
+        } else {
+            eprintln!("Codegen data cache has not been created yet, this may take a whie...");
+        }
+
+        let dll_data = Self::from_json_file(&input.json)?;
+        dll_data.write_cache_file(&input.cache)?;
+        Ok(dll_data)
+    }
+}
+
+/// Reads the header of a dump cache, returning whether it was written by this version of the
+/// generator
+fn read_cache_header(cache: &mut impl Read) -> bool {
+    let mut header = [0; 12];
+    cache.read_exact(&mut header).is_ok()
+        && header[..8] == CACHE_MAGIC[..]
+        && header[8..] == CACHE_VERSION.to_le_bytes()
+}
@@ -0,0 +1,57 @@
+//! Generates Rust bindings for the types of il2cpp games from a dump of their metadata.
+//!
+//! Besides the command line tool, bindings can be generated from a build script, with only the
+//! types a crate needs:
+//!
+//! ```no_run
+//! use il2cpp_codegen::{DllData, Generator};
+//! use std::path::Path;
+//!
+//! fn main() -> anyhow::Result<()> {
+//!     println!("cargo:rerun-if-changed=codegen.json");
+//!     let dump = DllData::from_json_file("codegen.json")?;
+//!     let out_dir = std::env::var("OUT_DIR")?;
+//!     Generator::new()
+//!         .include("UnityEngine.Transform")
+//!         .include("GlobalNamespace.PlayerController")
+//!         .root("crate::bindings")
+//!         .write_to(&dump, Path::new(&out_dir).join("bindings.rs"))?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The generated file is then included in the module given as its root. `include!`d files can't
+//! have inner attributes, so the bindings have none: lints are allowed on the module, and on
+//! nightly the crate root enables the features of `Toolchain::required_features`.
+//!
+//! ```ignore
+//! #![feature(arbitrary_enum_discriminant, generic_associated_types)]
+//!
+//! #[allow(warnings)]
+//! mod bindings {
+//!     include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+//! }
+//! ```
+
+#![warn(rust_2018_idioms)]
+
+mod compiler_generated;
+pub mod config;
+pub mod data;
+pub mod diff;
+pub mod error;
+mod format;
+mod generator;
+mod helpers;
+mod il2cpp_dumper;
+mod input;
+pub mod matching;
+mod names;
+pub mod schema;
+pub mod validate;
+mod writer;
+
+pub use config::Config;
+pub use data::DllData;
+pub use generator::{Generated, Generator};
+pub use names::Rename;
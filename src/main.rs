@@ -1,44 +1,161 @@
 #![warn(rust_2018_idioms)]
-#![feature(once_cell)]
 
-mod data;
-mod helpers;
-mod writer;
-
-use anyhow::{Context, Result};
-use data::DllData;
-use std::fs::File;
-use std::io::Write;
+use anyhow::{bail, Context, Result};
+use il2cpp_codegen::config::Toolchain;
+use il2cpp_codegen::{diff, matching, validate, Config, DllData, Generated, Generator};
+use std::fs;
 use std::path::Path;
 
-fn read_dll_data() -> Result<DllData> {
-    Ok(if Path::new("codegen.bc").exists() {
-        let input = File::open("codegen.bc").context("Failed to open JSON dump cache")?;
-        bincode::deserialize_from(input).context("Failed to parse JSON dump cache")?
+/// `diff <old.json> <new.json> [--json]`: prints the API changes between two dumps
+fn diff_dumps(args: &[String]) -> Result<()> {
+    let paths: Vec<&String> = args.iter().filter(|arg| !arg.starts_with("--")).collect();
+    let (old_path, new_path) = match paths[..] {
+        [old_path, new_path] => (old_path, new_path),
+        _ => bail!("Usage: il2cpp_codegen diff <old.json> <new.json> [--json]"),
+    };
+    let old = DllData::from_json_file(old_path)?;
+    let new = DllData::from_json_file(new_path)?;
+
+    let report = diff::diff(&old, &new);
+    if args.iter().any(|arg| arg == "--json") {
+        println!("{}", serde_json::to_string_pretty(&report)?);
     } else {
-        let input = File::open("codegen.json").context("Failed to open JSON dump")?;
-        println!("Codegen data cache has not been created yet, this may take a whie...");
-        let dll_data: DllData =
-            serde_json::from_reader(input).context("Failed to parse JSON dump")?;
-        let cache_file = File::create("codegen.bc").context("Failed to create JSON dump cache")?;
-        bincode::serialize_into(cache_file, &dll_data)
-            .context("Failed to serialize JSON dump cache")?;
-        dll_data
-    })
+        print!("{}", report);
+    }
+    Ok(())
+}
+
+/// `match <old.json> <new.json> [--min-confidence <0-1>]`: prints the proposed renames of types
+/// and members between two dumps as JSON
+fn match_dumps(args: &[String]) -> Result<()> {
+    let mut min_confidence = 0.5;
+    let mut paths = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--min-confidence" {
+            let value = args.next().context("`--min-confidence` requires a value")?;
+            min_confidence = value
+                .parse()
+                .with_context(|| format!("Invalid confidence `{}`", value))?;
+        } else {
+            paths.push(arg);
+        }
+    }
+    let (old_path, new_path) = match paths[..] {
+        [old_path, new_path] => (old_path, new_path),
+        _ => bail!("Usage: il2cpp_codegen match <old.json> <new.json> [--min-confidence <0-1>]"),
+    };
+    let old = DllData::from_json_file(old_path)?;
+    let new = DllData::from_json_file(new_path)?;
+
+    let report = matching::match_dumps(&old, &new, min_confidence);
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
+
+/// `validate <dump.json> [--json]`: prints the consistency problems of a dump, and fails if any of
+/// them is an error
+fn validate_dump(args: &[String]) -> Result<()> {
+    let paths: Vec<&String> = args.iter().filter(|arg| !arg.starts_with("--")).collect();
+    let path = match paths[..] {
+        [path] => path,
+        _ => bail!("Usage: il2cpp_codegen validate <dump.json> [--json]"),
+    };
+    let (dump, version) = DllData::read_json(path)?;
+
+    let report = validate::validate(&dump);
+    if args.iter().any(|arg| arg == "--json") {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("Schema version {}", version.number());
+        print!("{}", report);
+    }
+    if report.error_count() > 0 {
+        bail!("{} has {} errors", path, report.error_count());
+    }
+    Ok(())
+}
+
+/// Inner attributes of the generated file, which the bindings themselves don't have so that they
+/// can be `include!`d
+fn file_attributes(config: &Config) -> String {
+    let mut attributes = "#![allow(warnings)]\n".to_owned();
+    let features = config.output.features();
+    if !features.is_empty() {
+        attributes += &format!("#![feature({})]\n", features.join(", "));
+    }
+    attributes + "\n"
+}
+
+/// Prints the items which were stubbed or left out and the identifiers which were renamed
+fn print_summary(generated: &Generated<String>) {
+    for rename in &generated.renames {
+        eprintln!("Note: {}", rename);
+    }
+    if !generated.skipped.is_empty() {
+        eprintln!(
+            "Skipped {} items which could not be generated:",
+            generated.skipped.len()
+        );
+        for error in &generated.skipped {
+            let action = if error.stubs_type() {
+                "stubbed"
+            } else {
+                "left out"
+            };
+            eprintln!("    {} ({})", error, action);
+        }
+    }
 }
 
 fn main() -> Result<()> {
-    println!("Reading codegen data");
-    let json: DllData = read_dll_data()?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("diff") => return diff_dumps(&args[1..]),
+        Some("match") => return match_dumps(&args[1..]),
+        Some("validate") => return validate_dump(&args[1..]),
+        _ => {}
+    }
 
-    println!("Creating output file");
-    let mut output = File::create("generated.rs")?;
+    let config_path = match args.iter().position(|arg| arg == "--config") {
+        Some(i) => {
+            let path = args.get(i + 1).context("`--config` requires a path")?;
+            if !Path::new(path).exists() {
+                bail!("Config {} does not exist", path);
+            }
+            path.as_str()
+        }
+        None => "codegen.toml",
+    };
+    let mut config = Config::load(Path::new(config_path))?;
+    if args.iter().any(|arg| arg == "--nested-modules") {
+        config.output.nested_modules = true;
+    }
+    if args.iter().any(|arg| arg == "--keep-going") {
+        config.output.keep_going = true;
+    }
+    if args.iter().any(|arg| arg == "--stable") {
+        config.output.toolchain = Toolchain::Stable;
+    }
+    if let Some(i) = args.iter().position(|arg| arg == "--il2cpp-dumper") {
+        let dir = args
+            .get(i + 1)
+            .context("`--il2cpp-dumper` requires a directory")?;
+        config.input.il2cpp_dumper = Some(dir.into());
+    }
+
+    println!("Reading codegen data");
+    let json = DllData::load(&config.input)?;
 
-    println!("Generating tokens");
-    let tokens = json.write_tokens();
+    let output_path = config.output.path.clone();
+    let attributes = file_attributes(&config);
+    println!("Generating code");
+    let generated = Generator::with_config(config).generate(&json)?;
+    print_summary(&generated);
 
     println!("Writing code");
-    output.write_fmt(format_args!("{}", tokens))?;
+    fs::write(&output_path, attributes + &generated.code)
+        .with_context(|| format!("Failed to write {}", output_path.display()))?;
 
     println!("Done");
     Ok(())
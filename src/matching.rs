@@ -0,0 +1,554 @@
+//! Matching of types, methods and fields between two dumps by their structure, for members which
+//! were renamed or re-obfuscated between two versions of a game. Everything that kept its name is
+//! used as an anchor, and the remaining members are paired up by how similar their signatures and
+//! layouts are.
+
+use crate::data::{DllData, Field, Method, TypeData, TypeRef};
+use crate::diff::kind_name;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Proposed renames, each with a confidence between 0 and 1
+#[derive(Serialize, Debug, Default)]
+pub struct MatchReport {
+    pub types: Vec<TypeMatch>,
+    pub methods: Vec<MemberMatch>,
+    pub fields: Vec<MemberMatch>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TypeMatch {
+    pub old: String,
+    pub new: String,
+    pub confidence: f64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct MemberMatch {
+    /// Name of the declaring type in the new dump
+    #[serde(rename = "type")]
+    pub declaring_type: String,
+    pub old: String,
+    pub new: String,
+    pub old_signature: String,
+    pub new_signature: String,
+    pub confidence: f64,
+}
+
+/// Number of times type matching is repeated. Every round can recognize more types in the
+/// signatures of the remaining ones.
+const TYPE_ROUNDS: usize = 2;
+
+/// Number of candidates kept for each old type, the ones with the best scores
+const TYPE_CANDIDATES: usize = 8;
+
+/// Similarity of two multisets, 1 if both are empty
+fn similarity<T: Ord>(a: &[T], b: &[T]) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    let mut counts = BTreeMap::new();
+    for item in a {
+        *counts.entry(item).or_insert(0) += 1;
+    }
+    let mut common = 0;
+    for item in b {
+        if let Some(count) = counts.get_mut(item) {
+            if *count > 0 {
+                *count -= 1;
+                common += 1;
+            }
+        }
+    }
+    2.0 * common as f64 / (a.len() + b.len()) as f64
+}
+
+fn score(criteria: &[(f64, f64)]) -> f64 {
+    let total: f64 = criteria.iter().map(|(weight, _)| weight).sum();
+    criteria
+        .iter()
+        .map(|(weight, value)| weight * value)
+        .sum::<f64>()
+        / total
+}
+
+fn equal<T: PartialEq>(a: T, b: T) -> f64 {
+    if a == b {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// Pairs up candidates by descending score. The confidence of a pair is lowered by how close the
+/// best other candidate for either side came.
+fn assign(scores: &[(usize, usize, f64)], min_confidence: f64) -> Vec<(usize, usize, f64)> {
+    let mut runner_up: HashMap<(bool, usize), (f64, f64)> = HashMap::new();
+    for &(old, new, score) in scores {
+        for key in [(false, old), (true, new)] {
+            let best = runner_up.entry(key).or_insert((0.0, 0.0));
+            if score > best.0 {
+                *best = (score, best.0);
+            } else if score > best.1 {
+                best.1 = score;
+            }
+        }
+    }
+
+    let mut sorted = scores.to_vec();
+    sorted.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap());
+    let mut assigned_old = BTreeSet::new();
+    let mut assigned_new = BTreeSet::new();
+    let mut pairs = Vec::new();
+    for (old, new, score) in sorted {
+        if assigned_old.contains(&old) || assigned_new.contains(&new) {
+            continue;
+        }
+        let other_best = |key| {
+            let (best, second) = runner_up[&key];
+            if best == score {
+                second
+            } else {
+                best
+            }
+        };
+        let second_best = other_best((false, old)).max(other_best((true, new)));
+        let confidence = score - 0.5 * second_best;
+        if confidence < min_confidence {
+            continue;
+        }
+        assigned_old.insert(old);
+        assigned_new.insert(new);
+        pairs.push((old, new, confidence));
+    }
+    pairs
+}
+
+/// Translates type names of one dump into names the other dump agrees on. Types that could not
+/// be matched yet are unknown and only compare equal to other unknown types.
+struct TypeKeys<'a> {
+    dump: &'a DllData,
+    known: &'a HashMap<String, String>,
+}
+
+impl TypeKeys<'_> {
+    fn key(&self, type_ref: &TypeRef) -> String {
+        if type_ref.type_id < 0 {
+            // Generic parameters are only compared by whether they are one
+            return "T".to_owned();
+        }
+        let definition = TypeRef {
+            generics: Vec::new(),
+            ..type_ref.clone()
+        };
+        let mut key = self
+            .known
+            .get(&definition.cs_name(self.dump))
+            .cloned()
+            .unwrap_or_else(|| "?".to_owned());
+        if !type_ref.generics.is_empty() {
+            let args: Vec<String> = type_ref.generics.iter().map(|g| self.key(g)).collect();
+            key = format!("{}<{}>", key, args.join(", "));
+        }
+        key
+    }
+
+    fn method_key(&self, method: &Method) -> String {
+        let params: Vec<String> = method
+            .parameters
+            .iter()
+            .map(|p| self.key(&p.parameter_type))
+            .collect();
+        format!(
+            "{} {}({})",
+            self.key(&method.return_type),
+            method.specifiers.iter().any(|s| s == "static"),
+            params.join(", ")
+        )
+    }
+}
+
+/// The parts of a type compared when matching types
+struct TypeShape {
+    kind: String,
+    size: i32,
+    namespace: String,
+    parent: Option<String>,
+    declaring_type: Option<String>,
+    fields: Vec<String>,
+    methods: Vec<String>,
+    interfaces: Vec<String>,
+}
+
+impl TypeShape {
+    fn new(ty: &TypeData, keys: &TypeKeys<'_>) -> Self {
+        let declaring_types = ty.declaring_types(keys.dump);
+        let namespace = match declaring_types.first() {
+            Some(outermost) => outermost.namespace.clone(),
+            None => ty.this.namespace.clone(),
+        };
+        let fields = ty
+            .instance_fields
+            .iter()
+            .chain(&ty.static_fields)
+            .map(|f| format!("{}@{}", keys.key(&f.field_type), f.offset))
+            .collect();
+        let methods = ty.methods.iter().map(|m| keys.method_key(m)).collect();
+        let interfaces = ty
+            .implementing_interfaces
+            .iter()
+            .map(|i| keys.key(i))
+            .collect();
+        Self {
+            kind: kind_name(&ty.type_enum),
+            size: ty.size,
+            namespace,
+            parent: ty.parent.as_ref().map(|p| keys.key(p)),
+            declaring_type: ty.this.declaring_type.as_ref().map(|d| keys.key(d)),
+            fields,
+            methods,
+            interfaces,
+        }
+    }
+
+    /// Keys of the groups of types this type is compared with. Comparing every type with every
+    /// other one is too slow for real dumps, so only types of the same kind that share their
+    /// layout, or their base class or declaring type once it is known, are compared.
+    fn candidate_keys(&self) -> Vec<String> {
+        let (kind, fields, methods) = (&self.kind, self.fields.len(), self.methods.len());
+        let mut keys = vec![format!("{} {} fields {} methods", kind, fields, methods)];
+        let is_known = |key: &&String| !key.contains('?');
+        if let Some(parent) = self.parent.as_ref().filter(is_known) {
+            keys.push(format!("{} {} fields: {}", kind, fields, parent));
+            keys.push(format!("{} {} methods: {}", kind, methods, parent));
+        }
+        if let Some(declaring_type) = self.declaring_type.as_ref().filter(is_known) {
+            keys.push(format!("{} in {}", kind, declaring_type));
+        }
+        keys
+    }
+
+    fn score(&self, other: &TypeShape) -> f64 {
+        score(&[
+            (1.0, equal(self.size, other.size)),
+            (1.0, equal(&self.namespace, &other.namespace)),
+            (2.0, equal(&self.parent, &other.parent)),
+            (2.0, equal(&self.declaring_type, &other.declaring_type)),
+            (3.0, similarity(&self.fields, &other.fields)),
+            (3.0, similarity(&self.methods, &other.methods)),
+            (1.0, similarity(&self.interfaces, &other.interfaces)),
+        ])
+    }
+}
+
+fn method_score(
+    old: &Method,
+    old_keys: &TypeKeys<'_>,
+    new: &Method,
+    new_keys: &TypeKeys<'_>,
+) -> Option<f64> {
+    if old.parameters.len() != new.parameters.len() {
+        return None;
+    }
+    let params = if old.parameters.is_empty() {
+        1.0
+    } else {
+        let equal_params = old
+            .parameters
+            .iter()
+            .zip(&new.parameters)
+            .filter(|(o, n)| old_keys.key(&o.parameter_type) == new_keys.key(&n.parameter_type))
+            .count();
+        equal_params as f64 / old.parameters.len() as f64
+    };
+    let is_static = |m: &Method| m.specifiers.iter().any(|s| s == "static");
+    Some(score(&[
+        (3.0, params),
+        (
+            2.0,
+            equal(
+                old_keys.key(&old.return_type),
+                new_keys.key(&new.return_type),
+            ),
+        ),
+        (1.0, equal(old.slot, new.slot)),
+        (1.0, equal(is_static(old), is_static(new))),
+        (
+            1.0,
+            equal(old.generic_parameters.len(), new.generic_parameters.len()),
+        ),
+    ]))
+}
+
+fn field_score(
+    old: &(&Field, bool),
+    old_keys: &TypeKeys<'_>,
+    new: &(&Field, bool),
+    new_keys: &TypeKeys<'_>,
+) -> f64 {
+    score(&[
+        (2.0, equal(old.0.offset, new.0.offset)),
+        (
+            2.0,
+            equal(
+                old_keys.key(&old.0.field_type),
+                new_keys.key(&new.0.field_type),
+            ),
+        ),
+        (1.0, equal(old.1, new.1)),
+    ])
+}
+
+fn fields(ty: &TypeData) -> Vec<(&Field, bool)> {
+    let instance_fields = ty.instance_fields.iter().map(|f| (f, false));
+    instance_fields
+        .chain(ty.static_fields.iter().map(|f| (f, true)))
+        .collect()
+}
+
+struct Matcher<'a> {
+    old: &'a DllData,
+    new: &'a DllData,
+    /// Names of the old types, mapped to the names of the new types they correspond to
+    old_to_new: HashMap<String, String>,
+    /// Names of the new types that have a counterpart, mapped to themselves
+    new_known: HashMap<String, String>,
+    min_confidence: f64,
+}
+
+impl<'a> Matcher<'a> {
+    fn old_keys(&self) -> TypeKeys<'_> {
+        TypeKeys {
+            dump: self.old,
+            known: &self.old_to_new,
+        }
+    }
+
+    fn new_keys(&self) -> TypeKeys<'_> {
+        TypeKeys {
+            dump: self.new,
+            known: &self.new_known,
+        }
+    }
+
+    fn insert(&mut self, old: String, new: String) {
+        self.new_known.insert(new.clone(), new.clone());
+        self.old_to_new.insert(old, new);
+    }
+
+    fn match_types(&mut self) -> Vec<TypeMatch> {
+        let old_names: Vec<String> = self
+            .old
+            .types
+            .iter()
+            .map(|t| t.cs_full_name(self.old))
+            .collect();
+        let new_names: Vec<String> = self
+            .new
+            .types
+            .iter()
+            .map(|t| t.cs_full_name(self.new))
+            .collect();
+        let old_set: BTreeSet<&String> = old_names.iter().collect();
+        let new_set: BTreeSet<&String> = new_names.iter().collect();
+        for name in old_set.intersection(&new_set) {
+            self.insert(name.to_string(), name.to_string());
+        }
+        let unmatched_old: Vec<usize> = (0..old_names.len())
+            .filter(|&i| !new_set.contains(&old_names[i]))
+            .collect();
+        let unmatched_new: Vec<usize> = (0..new_names.len())
+            .filter(|&i| !old_set.contains(&new_names[i]))
+            .collect();
+
+        let anchors = (self.old_to_new.clone(), self.new_known.clone());
+        let mut pairs = Vec::new();
+        for _ in 0..TYPE_ROUNDS {
+            let old_shapes: Vec<TypeShape> = unmatched_old
+                .iter()
+                .map(|&i| TypeShape::new(&self.old.types[i], &self.old_keys()))
+                .collect();
+            let new_shapes: Vec<TypeShape> = unmatched_new
+                .iter()
+                .map(|&i| TypeShape::new(&self.new.types[i], &self.new_keys()))
+                .collect();
+
+            let mut groups: HashMap<String, Vec<usize>> = HashMap::new();
+            for (new_index, new_shape) in new_shapes.iter().enumerate() {
+                for key in new_shape.candidate_keys() {
+                    groups.entry(key).or_default().push(new_index);
+                }
+            }
+            let mut scores = Vec::new();
+            for (old_index, old_shape) in old_shapes.iter().enumerate() {
+                let mut candidates: Vec<usize> = old_shape
+                    .candidate_keys()
+                    .iter()
+                    .filter_map(|key| groups.get(key))
+                    .flatten()
+                    .copied()
+                    .collect();
+                candidates.sort_unstable();
+                candidates.dedup();
+                let mut candidate_scores: Vec<(usize, usize, f64)> = candidates
+                    .into_iter()
+                    .map(|new_index| {
+                        let score = old_shape.score(&new_shapes[new_index]);
+                        (old_index, new_index, score)
+                    })
+                    .collect();
+                candidate_scores.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap());
+                candidate_scores.truncate(TYPE_CANDIDATES);
+                scores.extend(candidate_scores);
+            }
+            pairs = assign(&scores, self.min_confidence);
+            self.old_to_new = anchors.0.clone();
+            self.new_known = anchors.1.clone();
+            for &(old_index, new_index, _) in &pairs {
+                let old_name = old_names[unmatched_old[old_index]].clone();
+                let new_name = new_names[unmatched_new[new_index]].clone();
+                self.insert(old_name, new_name);
+            }
+        }
+
+        let mut matches: Vec<TypeMatch> = pairs
+            .into_iter()
+            .map(|(old_index, new_index, confidence)| TypeMatch {
+                old: old_names[unmatched_old[old_index]].clone(),
+                new: new_names[unmatched_new[new_index]].clone(),
+                confidence,
+            })
+            .collect();
+        matches.sort_by(|a, b| a.old.cmp(&b.old));
+        matches
+    }
+
+    fn match_members(&self, old_ty: &TypeData, new_ty: &TypeData, report: &mut MatchReport) {
+        let declaring_type = new_ty.cs_full_name(self.new);
+        let (old_keys, new_keys) = (self.old_keys(), self.new_keys());
+
+        // Only overloads which disappeared can have been renamed to one that appeared. Renamed
+        // overloads are recognized by their name and parameter types, as other overloads of the
+        // same name may still exist.
+        let overload_key = |m: &Method, keys: &TypeKeys<'_>| {
+            let params: Vec<String> = m
+                .parameters
+                .iter()
+                .map(|p| keys.key(&p.parameter_type))
+                .collect();
+            format!("{}({})", m.name, params.join(", "))
+        };
+        let old_overloads: BTreeSet<String> = old_ty
+            .methods
+            .iter()
+            .map(|m| overload_key(m, &old_keys))
+            .collect();
+        let new_overloads: BTreeSet<String> = new_ty
+            .methods
+            .iter()
+            .map(|m| overload_key(m, &new_keys))
+            .collect();
+        let old_methods: Vec<&Method> = old_ty
+            .methods
+            .iter()
+            .filter(|m| !new_overloads.contains(&overload_key(m, &old_keys)))
+            .collect();
+        let new_methods: Vec<&Method> = new_ty
+            .methods
+            .iter()
+            .filter(|m| !old_overloads.contains(&overload_key(m, &new_keys)))
+            .collect();
+        let mut scores = Vec::new();
+        for (old_index, old_method) in old_methods.iter().enumerate() {
+            for (new_index, new_method) in new_methods.iter().enumerate() {
+                if let Some(score) = method_score(old_method, &old_keys, new_method, &new_keys) {
+                    scores.push((old_index, new_index, score));
+                }
+            }
+        }
+        for (old_index, new_index, confidence) in assign(&scores, self.min_confidence) {
+            let (old_method, new_method) = (old_methods[old_index], new_methods[new_index]);
+            // Methods which kept their name only changed their signature
+            if old_method.name == new_method.name {
+                continue;
+            }
+            report.methods.push(MemberMatch {
+                declaring_type: declaring_type.clone(),
+                old: old_method.name.clone(),
+                new: new_method.name.clone(),
+                old_signature: old_method.signature(self.old),
+                new_signature: new_method.signature(self.new),
+                confidence,
+            });
+        }
+
+        let old_fields = fields(old_ty);
+        let new_fields = fields(new_ty);
+        let old_unmatched: Vec<&(&Field, bool)> = old_fields
+            .iter()
+            .filter(|o| !new_fields.iter().any(|n| n.0.name == o.0.name))
+            .collect();
+        let new_unmatched: Vec<&(&Field, bool)> = new_fields
+            .iter()
+            .filter(|n| !old_fields.iter().any(|o| o.0.name == n.0.name))
+            .collect();
+        let mut scores = Vec::new();
+        for (old_index, old_field) in old_unmatched.iter().enumerate() {
+            for (new_index, new_field) in new_unmatched.iter().enumerate() {
+                let score = field_score(old_field, &old_keys, new_field, &new_keys);
+                scores.push((old_index, new_index, score));
+            }
+        }
+        for (old_index, new_index, confidence) in assign(&scores, self.min_confidence) {
+            let (old_field, new_field) = (old_unmatched[old_index].0, new_unmatched[new_index].0);
+            report.fields.push(MemberMatch {
+                declaring_type: declaring_type.clone(),
+                old: old_field.name.clone(),
+                new: new_field.name.clone(),
+                old_signature: format!(
+                    "{} {}",
+                    old_field.field_type.cs_name(self.old),
+                    old_field.name
+                ),
+                new_signature: format!(
+                    "{} {}",
+                    new_field.field_type.cs_name(self.new),
+                    new_field.name
+                ),
+                confidence,
+            });
+        }
+    }
+}
+
+/// Proposes renames for the types, methods and fields of `old` that no longer exist under the
+/// same name in `new`. Matches with a confidence below `min_confidence` are left out.
+pub fn match_dumps(old: &DllData, new: &DllData, min_confidence: f64) -> MatchReport {
+    let mut matcher = Matcher {
+        old,
+        new,
+        old_to_new: HashMap::new(),
+        new_known: HashMap::new(),
+        min_confidence,
+    };
+    let mut report = MatchReport {
+        types: matcher.match_types(),
+        ..Default::default()
+    };
+
+    let new_types: HashMap<String, &TypeData> = new
+        .types
+        .iter()
+        .map(|ty| (ty.cs_full_name(new), ty))
+        .collect();
+    for old_ty in &old.types {
+        let new_ty = matcher
+            .old_to_new
+            .get(&old_ty.cs_full_name(old))
+            .and_then(|name| new_types.get(name));
+        if let Some(new_ty) = new_ty {
+            matcher.match_members(old_ty, new_ty, &mut report);
+        }
+    }
+    report
+}
@@ -0,0 +1,131 @@
+//! Allocation of the identifiers used in the generated code. C# allows names that clash once they
+//! are turned into Rust identifiers, or that clash with the items the generator adds itself, so
+//! every scope of the generated code hands out its identifiers through a `Scope`.
+
+use crate::error::ErrorKind;
+use crate::helpers::fix_ident;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Names that generated code refers to without a path, which no generated item may shadow
+pub const PRELUDE_NAMES: &[&str] = &[
+    "std", "Result", "bool", "char", "str", "f32", "f64", "i8", "i16", "i32", "i64", "i128",
+    "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+];
+
+/// An identifier which was suffixed to avoid a name collision
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rename {
+    /// Description of the scope the names collided in, like `module UnityEngine`
+    pub scope: String,
+    pub name: String,
+    pub renamed: String,
+}
+
+impl fmt::Display for Rename {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "renamed `{}` to `{}` in {} to avoid a name collision",
+            self.name, self.renamed, self.scope
+        )
+    }
+}
+
+/// The identifiers in use in one scope of the generated code, such as a module, the fields of a
+/// struct or the generic parameters of a method
+pub struct Scope<'a> {
+    /// Description of the scope used when recording renames
+    description: String,
+    taken: HashSet<String>,
+    renames: &'a RefCell<Vec<Rename>>,
+}
+
+impl<'a> Scope<'a> {
+    /// A scope which records its renames in `renames`
+    pub fn new(renames: &'a RefCell<Vec<Rename>>, description: impl Into<String>) -> Self {
+        Self {
+            description: description.into(),
+            taken: HashSet::new(),
+            renames,
+        }
+    }
+
+    /// Marks `name` as used by something the generator emits itself
+    pub fn reserve(&mut self, name: impl Into<String>) {
+        self.taken.insert(name.into());
+    }
+
+    pub fn reserve_all<S: AsRef<str>>(&mut self, names: impl IntoIterator<Item = S>) {
+        for name in names {
+            self.reserve(name.as_ref());
+        }
+    }
+
+    /// Turns `name` into an identifier that is unique in the scope. If the identifier is taken,
+    /// the lowest free numeric suffix is appended, so the result only depends on the names
+    /// allocated before. Every rename is recorded.
+    pub fn allocate(&mut self, name: &str) -> String {
+        let ident = fix_ident(name);
+        if self.taken.insert(ident.clone()) {
+            return ident;
+        }
+        let renamed = (1..)
+            .map(|i| format!("{}_{}", ident, i))
+            .find(|candidate| !self.taken.contains(candidate))
+            .unwrap();
+        self.renames.borrow_mut().push(Rename {
+            scope: self.description.clone(),
+            name: name.to_owned(),
+            renamed: renamed.clone(),
+        });
+        self.taken.insert(renamed.clone());
+        renamed
+    }
+}
+
+/// Identifiers of the items that are referred to from other parts of the generated code, which
+/// are allocated before any code is written
+#[derive(Debug, Default)]
+pub struct Names {
+    /// Names of modules, by the names they were allocated for along the path to the module
+    pub modules: HashMap<Vec<String>, String>,
+    /// Names of types in their module, by type id
+    pub types: HashMap<usize, String>,
+    /// Names of the flattened aliases of types emitted in nested modules, by type id
+    pub aliases: HashMap<usize, String>,
+    /// Names of the generic parameters of types, by type id
+    pub generics: HashMap<usize, Vec<String>>,
+}
+
+impl Names {
+    /// Identifiers of the modules along `path`, which holds the names the modules were allocated
+    /// for
+    pub fn module_path(&self, path: &[String]) -> Vec<String> {
+        (1..=path.len())
+            .map(|len| self.modules[&path[..len]].clone())
+            .collect()
+    }
+
+    pub fn type_name(&self, id: usize) -> Result<&str, ErrorKind> {
+        self.types
+            .get(&id)
+            .map(String::as_str)
+            .ok_or(ErrorKind::UnnamedType(id))
+    }
+
+    pub fn alias(&self, id: usize) -> Result<&str, ErrorKind> {
+        self.aliases
+            .get(&id)
+            .map(String::as_str)
+            .ok_or(ErrorKind::UnnamedType(id))
+    }
+
+    pub fn generics(&self, id: usize) -> Result<&[String], ErrorKind> {
+        self.generics
+            .get(&id)
+            .map(Vec::as_slice)
+            .ok_or(ErrorKind::UnnamedType(id))
+    }
+}
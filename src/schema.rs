@@ -0,0 +1,58 @@
+//! Versions of the JSON schema written by the dumper. Dumps of all versions are parsed into
+//! `DllData`, where fields added by later versions take their default, and are then converted to
+//! the latest version so that the writer only has to deal with one.
+//!
+//! Dumps may declare their version in a top-level `Version` field. The dumper does not write one,
+//! so dumps without it are read as version 1, the format it writes today. Dumps of versions newer
+//! than the latest known one are read as the latest version, ignoring the fields it does not know.
+//! Il2CppDumper output is not a version of this schema, and is converted by `il2cpp_dumper`.
+//!
+//! Renamed or retyped fields are not supported: there is no known dump that renames a field, and
+//! one would need a `#[serde(alias)]` in `data` plus a fixture using the old name.
+
+use crate::data::DllData;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SchemaVersion {
+    /// The format of the dumper. Generic constraints are only given for whole types, not for
+    /// their generic parameters, and not at all for generic methods.
+    V1,
+}
+
+impl SchemaVersion {
+    pub const LATEST: SchemaVersion = SchemaVersion::V1;
+
+    pub fn from_number(number: u32) -> Option<Self> {
+        match number {
+            1 => Some(SchemaVersion::V1),
+            _ => None,
+        }
+    }
+
+    pub fn number(self) -> u32 {
+        match self {
+            SchemaVersion::V1 => 1,
+        }
+    }
+}
+
+/// Converts a dump of `version` to the latest version
+pub fn upgrade(dump: &mut DllData, version: SchemaVersion) {
+    match version {
+        SchemaVersion::V1 => upgrade_v1(dump),
+    }
+}
+
+/// Constraints of version 1 dumps are given for whole types, so they can only be attributed to a
+/// generic parameter if the type has no other. Constraints given for a parameter are kept, which
+/// is how dumps edited by hand constrain the parameters of other types.
+fn upgrade_v1(dump: &mut DllData) {
+    for ty in &mut dump.types {
+        let this = &mut ty.this;
+        if let [generic] = &mut this.generics[..] {
+            if generic.generic_parameter_constraints.is_empty() {
+                generic.generic_parameter_constraints = this.generic_parameter_constraints.clone();
+            }
+        }
+    }
+}
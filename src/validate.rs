@@ -0,0 +1,393 @@
+//! Consistency checks of a dump. Every problem found is a bug of the dumper, so a dump that passes
+//! validation but can't be generated points at a bug of the generator. Errors are problems the
+//! generator can't work around, while warnings are problems that lead to wrong or surprising
+//! generated code.
+
+use crate::data::{DllData, TypeData, TypeEnum, TypeRef};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+/// Size of the header of boxed objects, which dumps may or may not include in the size of value
+/// types
+const OBJECT_HEADER_SIZE: i32 = 16;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Category {
+    /// A type reference whose type id is not the index of a type of the dump
+    TypeIdOutOfRange,
+    /// A type nested in itself, directly or through other declaring types
+    DeclaringTypeCycle,
+    /// An enum without the instance field holding its value
+    MissingEnumValue,
+    /// A base class which is an interface, or an implemented interface which is not one
+    InvalidInheritance,
+    /// A type reference with another number of type arguments than the type has parameters
+    GenericArity,
+    /// Type definitions sharing their namespace, declaring type, name and number of generic
+    /// parameters
+    DuplicateName,
+    /// Negative sizes and offsets, classes smaller than their base class and fields past the end
+    /// of their type
+    ImplausibleSize,
+}
+
+impl Category {
+    pub fn is_error(self) -> bool {
+        matches!(
+            self,
+            Category::TypeIdOutOfRange | Category::DeclaringTypeCycle | Category::MissingEnumValue
+        )
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Category::TypeIdOutOfRange => "type ids out of range",
+            Category::DeclaringTypeCycle => "declaring type cycles",
+            Category::MissingEnumValue => "enums without a value field",
+            Category::InvalidInheritance => "invalid inheritance",
+            Category::GenericArity => "type argument counts not matching",
+            Category::DuplicateName => "duplicate names",
+            Category::ImplausibleSize => "implausible sizes",
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct Problem {
+    pub category: Category,
+    /// Full name of the type the problem was found in
+    pub type_name: String,
+    pub message: String,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct ValidationReport {
+    pub problems: Vec<Problem>,
+}
+
+impl ValidationReport {
+    pub fn error_count(&self) -> usize {
+        self.problems
+            .iter()
+            .filter(|problem| problem.category.is_error())
+            .count()
+    }
+
+    pub fn warning_count(&self) -> usize {
+        self.problems.len() - self.error_count()
+    }
+
+    fn add(&mut self, category: Category, ty: &TypeData, dump: &DllData, message: String) {
+        self.problems.push(Problem {
+            category,
+            type_name: ty.cs_full_name(dump),
+            message,
+        });
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut categories: BTreeMap<Category, Vec<&Problem>> = BTreeMap::new();
+        for problem in &self.problems {
+            categories
+                .entry(problem.category)
+                .or_default()
+                .push(problem);
+        }
+        for (category, problems) in categories {
+            let severity = if category.is_error() {
+                "error"
+            } else {
+                "warning"
+            };
+            writeln!(
+                f,
+                "{} {} ({}):",
+                problems.len(),
+                category.description(),
+                severity
+            )?;
+            for problem in problems {
+                writeln!(f, "    {}: {}", problem.type_name, problem.message)?;
+            }
+        }
+        writeln!(
+            f,
+            "{} errors, {} warnings",
+            self.error_count(),
+            self.warning_count()
+        )
+    }
+}
+
+/// Checks the type references of a type, and the references they are made of
+struct RefChecker<'a> {
+    dump: &'a DllData,
+    ty: &'a TypeData,
+}
+
+impl RefChecker<'_> {
+    /// Checks `type_ref`, which is used as `usage`, like "field `x`"
+    fn check(&self, report: &mut ValidationReport, usage: &str, type_ref: &TypeRef) {
+        if type_ref.type_id >= 0 {
+            match self.dump.get(type_ref) {
+                None => report.add(
+                    Category::TypeIdOutOfRange,
+                    self.ty,
+                    self.dump,
+                    format!(
+                        "{} refers to type id {} (`{}`)",
+                        usage, type_ref.type_id, type_ref.name
+                    ),
+                ),
+                Some(target) => {
+                    let params = target.this.generics.len();
+                    let args = type_ref.generics.len();
+                    if args > 0 && args != params {
+                        report.add(
+                            Category::GenericArity,
+                            self.ty,
+                            self.dump,
+                            format!(
+                                "{} passes {} type arguments to `{}`, which has {} parameters",
+                                usage,
+                                args,
+                                target.cs_full_name(self.dump),
+                                params
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+        for nested in type_ref
+            .generics
+            .iter()
+            .chain(&type_ref.generic_parameter_constraints)
+        {
+            self.check(report, usage, nested);
+        }
+    }
+
+    fn check_type(&self, report: &mut ValidationReport) {
+        let this = &self.ty.this;
+        let type_refs = this
+            .declaring_type
+            .iter()
+            .map(|r| ("the declaring type".to_owned(), r))
+            .chain(
+                this.element_type
+                    .iter()
+                    .map(|r| ("the element type".to_owned(), r)),
+            )
+            .chain(
+                this.generics
+                    .iter()
+                    .map(|r| ("a generic parameter".to_owned(), r)),
+            )
+            .chain(
+                this.generic_parameter_constraints
+                    .iter()
+                    .map(|r| ("a generic constraint".to_owned(), r)),
+            )
+            .chain(
+                self.ty
+                    .parent
+                    .iter()
+                    .map(|r| ("the base class".to_owned(), r)),
+            )
+            .chain(
+                self.ty
+                    .implementing_interfaces
+                    .iter()
+                    .map(|r| ("an interface".to_owned(), r)),
+            )
+            .chain(
+                self.ty
+                    .instance_fields
+                    .iter()
+                    .chain(&self.ty.static_fields)
+                    .map(|f| (format!("field `{}`", f.name), &f.field_type)),
+            )
+            .chain(
+                self.ty
+                    .properties
+                    .iter()
+                    .map(|p| (format!("property `{}`", p.name), &p.property_type)),
+            );
+        for (usage, type_ref) in type_refs {
+            self.check(report, &usage, type_ref);
+        }
+
+        for method in &self.ty.methods {
+            let usage = format!("method `{}`", method.name);
+            self.check(report, &usage, &method.return_type);
+            for parameter in &method.parameters {
+                self.check(report, &usage, &parameter.parameter_type);
+            }
+            for generic in &method.generic_parameters {
+                self.check(report, &usage, generic);
+            }
+        }
+    }
+}
+
+/// Whether following the declaring types of the type with id `id` leads back to it
+fn in_declaring_cycle(dump: &DllData, id: usize) -> bool {
+    let mut seen = HashSet::new();
+    let mut current = &dump.types[id];
+    while let Some(declaring_type) = &current.this.declaring_type {
+        if declaring_type.type_id as usize == id {
+            return true;
+        }
+        if !seen.insert(declaring_type.type_id) {
+            return false;
+        }
+        current = match dump.get(declaring_type) {
+            Some(declaring_type) => declaring_type,
+            None => return false,
+        };
+    }
+    false
+}
+
+fn check_inheritance(report: &mut ValidationReport, dump: &DllData, ty: &TypeData) {
+    let is_interface = |type_ref: &TypeRef| {
+        dump.get(type_ref)
+            .map(|target| matches!(target.type_enum, TypeEnum::Interface))
+    };
+    if let Some(parent) = &ty.parent {
+        if is_interface(parent) == Some(true) {
+            report.add(
+                Category::InvalidInheritance,
+                ty,
+                dump,
+                format!("the base class `{}` is an interface", parent.cs_name(dump)),
+            );
+        }
+    }
+    for interface in &ty.implementing_interfaces {
+        if is_interface(interface) == Some(false) {
+            report.add(
+                Category::InvalidInheritance,
+                ty,
+                dump,
+                format!(
+                    "the implemented interface `{}` is not an interface",
+                    interface.cs_name(dump)
+                ),
+            );
+        }
+    }
+}
+
+fn check_size(report: &mut ValidationReport, dump: &DllData, ty: &TypeData) {
+    // Generic type definitions have no layout of their own
+    if ty.this.is_generic_template {
+        return;
+    }
+    if ty.size < 0 {
+        report.add(
+            Category::ImplausibleSize,
+            ty,
+            dump,
+            format!("the size is negative ({})", ty.size),
+        );
+        return;
+    }
+    if let Some(parent) = ty.parent.as_ref().and_then(|parent| dump.get(parent)) {
+        if matches!(ty.type_enum, TypeEnum::Class) && ty.size < parent.size {
+            report.add(
+                Category::ImplausibleSize,
+                ty,
+                dump,
+                format!(
+                    "the size ({:#X}) is smaller than the size of the base class `{}` ({:#X})",
+                    ty.size,
+                    parent.cs_full_name(dump),
+                    parent.size
+                ),
+            );
+        }
+    }
+    // Field offsets include the object header, which only the size of classes is sure to include
+    let end = match ty.type_enum {
+        TypeEnum::Struct | TypeEnum::Enum => ty.size + OBJECT_HEADER_SIZE,
+        _ => ty.size,
+    };
+    for field in &ty.instance_fields {
+        if field.offset < 0 || (ty.size > 0 && field.offset >= end) {
+            report.add(
+                Category::ImplausibleSize,
+                ty,
+                dump,
+                format!(
+                    "field `{}` is at offset {:#X}, outside of the type of size {:#X}",
+                    field.name, field.offset, ty.size
+                ),
+            );
+        }
+    }
+}
+
+/// What tells type definitions apart: namespace, declaring type id, name and generic arity
+type DefinitionKey<'a> = (&'a str, Option<i32>, &'a str, usize);
+
+/// Instantiations of generic types share the key of their definition
+fn is_generic_instance(ty: &TypeData) -> bool {
+    ty.this
+        .generics
+        .iter()
+        .any(|generic| !generic.is_generic_parameter)
+}
+
+/// Checks the dump for consistency
+pub fn validate(dump: &DllData) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    let mut definitions: BTreeMap<DefinitionKey<'_>, Vec<usize>> = BTreeMap::new();
+    for (id, ty) in dump.types.iter().enumerate() {
+        RefChecker { dump, ty }.check_type(&mut report);
+        if in_declaring_cycle(dump, id) {
+            report.add(
+                Category::DeclaringTypeCycle,
+                ty,
+                dump,
+                "the type is nested in itself".to_owned(),
+            );
+        }
+        if matches!(ty.type_enum, TypeEnum::Enum) && ty.instance_fields.is_empty() {
+            report.add(
+                Category::MissingEnumValue,
+                ty,
+                dump,
+                "the enum has no instance field".to_owned(),
+            );
+        }
+        check_inheritance(&mut report, dump, ty);
+        check_size(&mut report, dump, ty);
+        if !is_generic_instance(ty) {
+            let key = (
+                ty.this.namespace.as_str(),
+                ty.this.declaring_type.as_ref().map(|d| d.type_id),
+                ty.this.name.as_str(),
+                ty.this.generics.len(),
+            );
+            definitions.entry(key).or_default().push(id);
+        }
+    }
+
+    for ids in definitions.into_values() {
+        if ids.len() > 1 {
+            let names: Vec<String> = ids.iter().map(ToString::to_string).collect();
+            report.problems.push(Problem {
+                category: Category::DuplicateName,
+                type_name: dump.types[ids[0]].cs_full_name(dump),
+                message: format!("the name is shared by the types {}", names.join(", ")),
+            });
+        }
+    }
+    report
+}
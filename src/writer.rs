@@ -1,9 +1,16 @@
+use crate::compiler_generated;
+use crate::config::{Backend, Case, CompilerGenerated, Config, RenameMap, TypeOverride};
 use crate::data::*;
-use crate::helpers::{create_ident, create_ident_trimmed};
-use proc_macro2::{Ident, TokenStream};
+use crate::error::{ErrorKind, GenerateError, MemberKind};
+use crate::generator::Generated;
+use crate::helpers::{create_ident, fix_ident, is_unnamable, is_valid_ident, matches_pattern};
+use crate::names::{Names, Rename, Scope, PRELUDE_NAMES};
+use anyhow::{bail, Context as _, Result};
+use proc_macro2::{Ident, Literal, TokenStream};
 use quote::{quote, ToTokens};
-use std::collections::HashMap;
-use std::lazy::SyncOnceCell;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 enum RefType {
     Ref,
@@ -11,107 +18,479 @@ enum RefType {
     Pointer,
 }
 
-macro_rules! replacement_types {
-    ( $( $name:ident => $replacement:ty ),* ) => {
-        #[derive(Debug)]
-        struct ReplacementTypes {
-            $(
-                $name: i32
-            ),*
+/// Types replaced by primitives, by namespace and name. `System.Object` and `System.String` are
+/// replaced by the corresponding runtime types.
+const DEFAULT_REPLACEMENTS: &[(&str, &str, &str)] = &[
+    ("System", "Single", "f32"),
+    ("System", "Double", "f64"),
+    ("System", "Void", "()"),
+    ("System", "Char", "u16"),
+    ("System", "Int16", "i16"),
+    ("System", "Int32", "i32"),
+    ("System", "Int64", "i64"),
+    ("System", "UInt16", "u16"),
+    ("System", "UInt32", "u32"),
+    ("System", "UInt64", "u64"),
+    ("System", "Byte", "u8"),
+    ("System", "SByte", "i8"),
+    ("System", "Boolean", "bool"),
+];
+
+/// Size of the header of boxed objects
+const OBJECT_HEADER_SIZE: i32 = 16;
+
+/// Size of the primitive types value types can be replaced with without configuring their size
+fn primitive_size(path: &str) -> Option<i32> {
+    Some(match path {
+        "()" => 0,
+        "bool" | "u8" | "i8" => 1,
+        "u16" | "i16" => 2,
+        "u32" | "i32" | "f32" => 4,
+        "u64" | "i64" | "f64" | "usize" | "isize" => 8,
+        _ => return None,
+    })
+}
+
+/// Replacement paths by type id. They are kept as strings because the first segment of each path
+/// is reserved, see `Context::reserved_names`.
+#[derive(Debug)]
+struct ReplacementTypes {
+    types: BTreeMap<i32, String>,
+    /// Replacements which implement the runtime's type trait, so the marker traits of generic
+    /// constraints can be implemented for them
+    bounded: BTreeSet<i32>,
+}
+
+impl ReplacementTypes {
+    fn new(types: &DllData, config: &Config) -> Result<Self> {
+        let mut replacements = BTreeMap::new();
+        // Types missing from the dump can't be referred to, so they don't need a replacement
+        let runtime_types = [
+            ("System", "Object", config.backend.object()),
+            ("System", "String", config.backend.string()),
+        ];
+        let default_replacements = DEFAULT_REPLACEMENTS
+            .iter()
+            .map(|&(namespace, name, replacement)| (namespace, name, replacement.to_owned()))
+            .chain(runtime_types.iter().cloned());
+        let mut bounded = BTreeSet::new();
+        for (namespace, name, replacement) in default_replacements {
+            if let Some(id) = types.find_type(namespace, name) {
+                replacements.insert(id, replacement);
+                bounded.insert(id);
+            }
+        }
+
+        for (full_name, replacement) in &config.replacements {
+            let id = types
+                .find_type_by_full_name(full_name)
+                .with_context(|| format!("Replaced type `{}` does not exist", full_name))?;
+            let path = replacement.path();
+
+            // The replacement is stored inline wherever a value type is used, so its layout has
+            // to match
+            let ty = &types.types[id as usize];
+            if !ty.pass_by_ref() && ty.size > 0 {
+                let size = replacement
+                    .size()
+                    .or_else(|| primitive_size(path))
+                    .with_context(|| {
+                        format!(
+                            "The size of `{}` replacing the value type `{}` has to be configured",
+                            path, full_name
+                        )
+                    })?;
+                // Dumps report the size of value types either with or without the object header
+                if size != ty.size && size + OBJECT_HEADER_SIZE != ty.size {
+                    bail!(
+                        "`{}` has a size of {} bytes, but the dump reports a size of {} bytes for the value type `{}`",
+                        path,
+                        size,
+                        ty.size,
+                        full_name
+                    );
+                }
+            }
+
+            replacements.insert(id, path.to_owned());
+            if replacement.bounds() {
+                bounded.insert(id);
+            } else {
+                bounded.remove(&id);
+            }
+        }
+
+        Ok(Self {
+            types: replacements,
+            bounded,
+        })
+    }
+
+    fn bounded(&self) -> impl Iterator<Item = (i32, TokenStream)> + '_ {
+        self.bounded
+            .iter()
+            .map(move |&id| (id, self.replace(id).unwrap()))
+    }
+
+    fn replace(&self, id: i32) -> Option<TokenStream> {
+        self.types.get(&id).map(|path| path.parse().unwrap())
+    }
+}
+
+/// Helper modules written at the root of the generated code
+const HELPER_MODULES: &[&str] = &[
+    "__bounds",
+    "__arrays",
+    "__nested_classes",
+    "__generic_methods",
+];
+
+/// Module compiler-generated types are emitted in, next to the types they would be emitted with
+const COMPILER_GENERATED_MODULE: &str = "__compiler_generated";
+
+/// State of the generation of the code for a dump, which is passed to all writers
+struct Context<'a> {
+    types: &'a DllData,
+    config: &'a Config,
+    replacements: ReplacementTypes,
+    /// Whether each type is generated, by type id
+    emitted_types: Vec<bool>,
+    names: Names,
+    /// Items which could not be generated, when generation keeps going
+    skipped: RefCell<Vec<GenerateError>>,
+    renames: RefCell<Vec<Rename>>,
+}
+
+impl<'a> Context<'a> {
+    /// The type referred to by `type_ref`
+    fn ty(&self, type_ref: &TypeRef) -> Result<&'a TypeData, ErrorKind> {
+        self.types
+            .get(type_ref)
+            .ok_or(ErrorKind::TypeIdOutOfRange(type_ref.type_id))
+    }
+
+    /// Records an item which can't be generated if generation keeps going, and fails otherwise
+    fn skip(&self, error: GenerateError) -> Result<(), GenerateError> {
+        if !self.config.output.keep_going {
+            return Err(error);
         }
+        self.skipped.borrow_mut().push(error);
+        Ok(())
+    }
 
-        impl ReplacementTypes {
-            fn replace(&self, id: i32) -> Option<TokenStream> {
-                return
-                $(
-                    if id == self.$name {
-                        Some(quote! { $replacement })
-                    } else
-                )*
-                { None }
+    /// Readable name for a compiler-generated type or member, unless they are generated as they
+    /// are
+    fn compiler_generated_name(&self, name: &str, is_async: bool) -> Option<String> {
+        match self.config.filter.compiler_generated {
+            CompilerGenerated::Keep => None,
+            CompilerGenerated::Skip | CompilerGenerated::Module => {
+                compiler_generated::readable_name(name, is_async)
             }
         }
-    };
+    }
+
+    /// Names no generated item may take, because generated code refers to them without a path.
+    /// This includes the first segment of the runtime and replacement paths.
+    fn reserved_names(&self) -> Vec<String> {
+        let replacements = self.replacements.types.values().cloned();
+        let runtime_paths = self
+            .config
+            .backend
+            .items()
+            .into_iter()
+            .map(|(_, path)| path);
+        let path_roots = replacements.chain(runtime_paths).filter_map(|path| {
+            let root = path.split("::").next().unwrap().trim();
+            is_valid_ident(root).then(|| root.to_owned())
+        });
+        PRELUDE_NAMES
+            .iter()
+            .map(|name| name.to_string())
+            .chain(path_roots)
+            .collect()
+    }
+
+    /// Whether the type can be referred to by generated code, either because it is generated or
+    /// because it is replaced
+    fn is_emitted(&self, id: i32) -> bool {
+        self.emitted_types.get(id as usize) == Some(&true)
+            || self.replacements.replace(id).is_some()
+    }
+
+    /// Path of a runtime item of the configured backend
+    fn runtime(&self, item: fn(&Backend) -> String) -> TokenStream {
+        item(&self.config.backend).parse().unwrap()
+    }
+
+    /// Path of the module the generated code is placed in
+    fn root(&self) -> TokenStream {
+        self.config.output.root.parse().unwrap()
+    }
 }
 
-replacement_types! {
-    single => f32,
-    double => f64,
-    void => (),
-    char => u16,
-    int16 => i16,
-    int32 => i32,
-    int64 => i64,
-    uint16 => u16,
-    uint32 => u32,
-    uint64 => u64,
-    byte => u8,
-    sbyte => i8,
-    boolean => bool,
-    object => quest_hook::libil2cpp::Il2CppObject,
-    string => quest_hook::libil2cpp::Il2CppString
+/// Number of dimensions of an array type called `Name[]`, `Name[,]`, ...
+fn array_rank(name: &str) -> usize {
+    match (name.strip_suffix(']'), name.rfind('[')) {
+        (Some(name), Some(start)) => name[start..].matches(',').count() + 1,
+        _ => 1,
+    }
 }
 
-static REPLACEMENT_TYPES: SyncOnceCell<ReplacementTypes> = SyncOnceCell::new();
+/// Name of the module the nested types of `type_name` are emitted in
+fn nested_module_name(type_name: &str) -> String {
+    type_name.to_owned() + "_"
+}
 
 impl TypeRef {
-    fn full_name(&self, types: &DllData) -> String {
-        let mut name = self.name.clone();
+    /// Name of the generated type, which can be changed by an override
+    fn rust_name<'a>(&'a self, cx: &Context<'a>) -> Cow<'a, str> {
+        match cx.types.get(self) {
+            Some(ty) => ty.rust_name(cx),
+            None => Cow::Borrowed(&self.name),
+        }
+    }
 
-        let mut current = &types[self];
-        while let Some(parent) = &current.this.declaring_type {
-            name.insert(0, '_');
-            name.insert_str(0, &parent.name);
-            current = &types[parent];
+    /// Short name of the type used in the fallback names of members, like `Int32` or
+    /// `StringArray`
+    fn fallback_part(&self, cx: &Context<'_>) -> String {
+        if self.is_array || self.is_pointer {
+            let suffix = if self.is_array { "Array" } else { "Ptr" };
+            return self.element_type(cx.types).fallback_part(cx) + suffix;
         }
+        let name = self.rust_name(cx);
+        // Generic types are called `Name`1`
+        let name = name.split('`').next().unwrap();
+        fix_ident(name).trim_matches('_').to_owned()
+    }
 
+    fn full_name(&self, cx: &Context<'_>) -> String {
+        let mut name = self.rust_name(cx).into_owned();
+        if let Some(ty) = cx.types.get(self) {
+            for declaring_type in ty.declaring_types(cx.types).iter().rev() {
+                name.insert(0, '_');
+                name.insert_str(0, &declaring_type.rust_name(cx));
+            }
+        }
         name
     }
 
-    fn get_qualified_name(&self, types: &DllData) -> TokenStream {
-        let mut name = self.name.clone();
-        let mut namespace = &self.namespace;
+    /// Whether the type and all types it is made of are available to generated code
+    fn is_emitted(&self, cx: &Context<'_>) -> Result<bool, ErrorKind> {
+        if self.is_array || self.is_pointer {
+            return self.element_type(cx.types).is_emitted(cx);
+        }
+        if self.type_id >= 0 {
+            cx.ty(self)?;
+            if !cx.is_emitted(self.type_id) {
+                return Ok(false);
+            }
+        }
+        for generic in &self.generics {
+            if !generic.is_emitted(cx)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
 
-        let mut current = &types[self];
-        while let Some(parent) = &current.this.declaring_type {
-            name.insert(0, '_');
-            name.insert_str(0, &parent.name);
-            namespace = &parent.namespace;
-            current = &types[parent];
+    /// Collects the ids of the type and all types it is made of
+    fn collect_type_ids(&self, types: &DllData, ids: &mut Vec<usize>) {
+        if self.is_array || self.is_pointer {
+            self.element_type(types).collect_type_ids(types, ids);
+            return;
+        }
+        if types.get(self).is_some() {
+            ids.push(self.type_id as usize);
+        }
+        for generic in &self.generics {
+            generic.collect_type_ids(types, ids);
         }
+    }
 
-        let namespace_tokens = namespace.split_terminator('.').map(create_ident);
-        let name_ident = create_ident(&name);
+    fn get_qualified_name(
+        &self,
+        cx: &Context<'_>,
+        substitutions: &HashMap<&str, TokenStream>,
+    ) -> Result<TokenStream, ErrorKind> {
+        let ty = cx.ty(self)?;
+        let modules = cx
+            .names
+            .module_path(&ty.module_path(cx))
+            .into_iter()
+            .map(|module| create_ident(&module));
+        let name_ident = ty.full_name(cx, self.type_id as usize)?;
         let generics = if !self.generics.is_empty() {
             let args = self
                 .generics
                 .iter()
-                .map(|tr| tr.write_qualified_name(types));
+                .map(|tr| tr.write_substituted_name(cx, substitutions))
+                .collect::<Result<Vec<_>, _>>()?;
             Some(quote! { < #( #args ),* > })
         } else {
             None
         };
 
-        quote! {
-            crate:: #( #namespace_tokens :: )* #name_ident #generics
-        }
+        let root = cx.root();
+        Ok(quote! {
+            #root:: #( #modules :: )* #name_ident #generics
+        })
     }
 
-    fn write_qualified_name(&self, types: &DllData) -> TokenStream {
-        if self.type_id >= 0 {
-            let replacements = REPLACEMENT_TYPES.get().unwrap();
+    /// Writes the path of the type. Generic parameters are written as their identifiers in
+    /// `substitutions`.
+    fn write_substituted_name(
+        &self,
+        cx: &Context<'_>,
+        substitutions: &HashMap<&str, TokenStream>,
+    ) -> Result<TokenStream, ErrorKind> {
+        if self.is_array {
+            self.write_array_type(cx, substitutions)
+        } else if self.type_id >= 0 {
+            let replacements = &cx.replacements;
             if let Some(replacement) = replacements.replace(self.type_id) {
-                replacement
+                Ok(replacement)
+            } else {
+                self.get_qualified_name(cx, substitutions)
+            }
+        } else if let Some(substitution) = substitutions.get(self.name.as_str()) {
+            Ok(substitution.clone())
+        } else {
+            Ok(create_ident(&self.name).into_token_stream())
+        }
+    }
+
+    /// Writes a generic parameter declaration with its C# constraints translated into bounds
+    fn write_generic_param(
+        &self,
+        cx: &Context<'_>,
+        substitutions: &HashMap<&str, TokenStream>,
+    ) -> Result<TokenStream, ErrorKind> {
+        let name = self.write_substituted_name(cx, substitutions)?;
+
+        let mut is_value_type = self.special_constraints.iter().any(|s| s == "struct");
+        let is_reference_type = self.special_constraints.iter().any(|s| s == "class");
+        let root = cx.root();
+        let mut bounds = vec![cx.runtime(Backend::type_trait)];
+        for constraint in &self.generic_parameter_constraints {
+            if !constraint.is_emitted(cx)? {
+                continue;
+            }
+            match (constraint.namespace.as_str(), constraint.name.as_str()) {
+                ("System", "ValueType") => is_value_type = true,
+                ("System", "Object") => {}
+                _ => {
+                    let ty = constraint.write_substituted_name(cx, substitutions)?;
+                    bounds.push(quote! { #root::__bounds::SubtypeOf<#ty> });
+                }
+            }
+        }
+        // il2cpp objects are always created by the runtime, so `new()` has no Rust counterpart
+        if is_value_type {
+            bounds.push(quote! { #root::__bounds::ValueType });
+        }
+        if is_reference_type {
+            bounds.push(quote! { #root::__bounds::ReferenceType });
+        }
+
+        Ok(quote! { #name: #( #bounds )+* })
+    }
+
+    /// Name of the type as `il2cpp_type_get_name` formats it, like
+    /// `System.Collections.Generic.List`1<T>` or `T[]`
+    fn il2cpp_name(&self, types: &DllData) -> String {
+        if self.is_array || self.is_pointer {
+            let mut name = self.element_type(types).il2cpp_name(types);
+            if self.is_pointer {
+                name.push('*');
             } else {
-                self.get_qualified_name(types)
+                let mut rank = array_rank(&self.name);
+                if let Some(ty) = types.get(self) {
+                    rank = rank.max(array_rank(&ty.this.name));
+                }
+                name.push('[');
+                name.push_str(&",".repeat(rank - 1));
+                name.push(']');
             }
+            return name;
+        }
+        let mut name = match types.get(self) {
+            Some(ty) => ty.cs_full_name(types).replace('/', "."),
+            None => self.name.clone(),
+        };
+        if !self.generics.is_empty() {
+            let args: Vec<String> = self.generics.iter().map(|g| g.il2cpp_name(types)).collect();
+            name = format!("{}<{}>", name, args.join(","));
+        }
+        name
+    }
+
+    /// The element type of an array or pointer type. Dumps that don't provide element types refer
+    /// to the element type directly and only mark the reference as an array or pointer.
+    fn element_type<'a>(&'a self, types: &'a DllData) -> Cow<'a, TypeRef> {
+        let element_type = types.get(self).and_then(|ty| ty.this.element_type.as_ref());
+        match element_type {
+            Some(element_type) => Cow::Borrowed(element_type),
+            None => Cow::Owned(TypeRef {
+                is_array: false,
+                is_pointer: false,
+                ..self.clone()
+            }),
+        }
+    }
+
+    fn write_array_type(
+        &self,
+        cx: &Context<'_>,
+        substitutions: &HashMap<&str, TokenStream>,
+    ) -> Result<TokenStream, ErrorKind> {
+        let element_type = self.element_type(cx.types);
+        let element = if element_type.is_array || element_type.is_pointer {
+            // Jagged arrays hold pointers to their inner arrays
+            element_type.write_instance_type(cx, RefType::Pointer, substitutions)?
         } else {
-            create_ident(&self.name).into_token_stream()
+            element_type.write_substituted_name(cx, substitutions)?
+        };
+
+        let mut rank = array_rank(&self.name);
+        if self.type_id >= 0 {
+            rank = rank.max(array_rank(&cx.ty(self)?.this.name));
         }
+        Ok(if rank > 1 {
+            let rank = Literal::usize_unsuffixed(rank);
+            let root = cx.root();
+            quote! { #root::__arrays::MultidimensionalArray< #element, #rank > }
+        } else {
+            let array = cx.runtime(Backend::array);
+            quote! { #array< #element > }
+        })
     }
 
-    fn write_instance_type(&self, types: &DllData, ref_type: RefType) -> TokenStream {
-        let prefix = if self.type_id > 0 && types[self].pass_by_ref() {
+    fn write_pointer_type(
+        &self,
+        cx: &Context<'_>,
+        substitutions: &HashMap<&str, TokenStream>,
+    ) -> Result<TokenStream, ErrorKind> {
+        let element_type = self.element_type(cx.types);
+        let pointee = if element_type.namespace == "System" && element_type.name == "Void" {
+            quote! { std::ffi::c_void }
+        } else {
+            element_type.write_instance_type(cx, RefType::Pointer, substitutions)?
+        };
+        Ok(quote! { *mut #pointee })
+    }
+
+    fn write_instance_type(
+        &self,
+        cx: &Context<'_>,
+        ref_type: RefType,
+        substitutions: &HashMap<&str, TokenStream>,
+    ) -> Result<TokenStream, ErrorKind> {
+        if self.is_pointer {
+            return self.write_pointer_type(cx, substitutions);
+        }
+
+        // Arrays are always reference types, whatever their element type is
+        let prefix = if self.is_array || (self.type_id > 0 && cx.ty(self)?.pass_by_ref()) {
             Some(match ref_type {
                 RefType::Ref => quote! { &mut },
                 RefType::StaticRef => quote! { &'static mut },
@@ -120,122 +499,249 @@ impl TypeRef {
         } else {
             None
         };
-        let ty = self.write_qualified_name(types);
-        if self.is_array {
-            quote! { #prefix quest_hook::libil2cpp::Il2CppArray< #ty > }
-        } else if self.type_id > 0 {
+        let ty = self.write_substituted_name(cx, substitutions)?;
+        Ok(if self.is_array || self.type_id > 0 {
             quote! { #prefix #ty }
         } else {
             // Generic type parameter
+            let type_trait = cx.runtime(Backend::type_trait);
             if let RefType::Ref = ref_type {
-                quote! { < #ty as quest_hook::libil2cpp::Type>::Held<'_> }
+                quote! { < #ty as #type_trait>::Held<'_> }
             } else {
-                quote! { < #ty as quest_hook::libil2cpp::Type>::HeldRaw }
+                quote! { < #ty as #type_trait>::HeldRaw }
             }
-        }
+        })
     }
 }
 
 impl Field {
-    fn write_tokens(&self, types: &DllData) -> TokenStream {
-        let name = create_ident_trimmed(&self.name);
-        let type_ref = self.field_type.write_instance_type(types, RefType::Pointer);
-        quote! {
-            pub #name: #type_ref
-        }
+    /// Name for fields whose name has no characters that can be used in an identifier, like
+    /// `field_Int32_0`
+    fn fallback_name(&self, position: usize, cx: &Context<'_>) -> String {
+        format!("field_{}_{}", self.field_type.fallback_part(cx), position)
+    }
+
+    fn write_tokens(
+        &self,
+        name: &str,
+        cx: &Context<'_>,
+        substitutions: &HashMap<&str, TokenStream>,
+    ) -> Result<TokenStream, ErrorKind> {
+        let name = create_ident(name);
+        let type_ref = self
+            .field_type
+            .write_instance_type(cx, RefType::Pointer, substitutions)?;
+        // Skipped fields are still needed for the layout of the type
+        let visibility = (cx.config.filter.compiler_generated != CompilerGenerated::Skip
+            || !compiler_generated::is_compiler_generated(&self.name, &self.attributes))
+        .then(|| quote! { pub });
+        Ok(quote! {
+            #visibility #name: #type_ref
+        })
     }
 }
 
 impl Method {
-    fn write_static_body(&self, args: impl Iterator<Item = Ident>) -> TokenStream {
+    /// Generic methods have to be inflated with the il2cpp classes of the type arguments before
+    /// they can be invoked. Instantiations are cached by `__generic_methods::instantiate`. The
+    /// definition is looked up by the il2cpp names of the parameter types, which tell overloads
+    /// apart.
+    fn write_generic_body(
+        &self,
+        cx: &Context<'_>,
+        is_instance: bool,
+        args: impl Iterator<Item = Ident>,
+        type_args: &[TokenStream],
+    ) -> TokenStream {
         let name_str = &self.name;
+        let signature = self.parameters.iter().map(|p| {
+            let mut name = p.parameter_type.il2cpp_name(cx.types);
+            if !matches!(
+                p.modifier,
+                ParameterModifier::None | ParameterModifier::Params
+            ) {
+                name.push('&');
+            }
+            name
+        });
+        let this = if is_instance {
+            quote! { self }
+        } else {
+            quote! { () }
+        };
+        let type_trait = cx.runtime(Backend::type_trait);
+        let root = cx.root();
 
+        // The runtime hands out exceptions mutably, while generated methods return them shared.
+        // Arguments are passed as a tuple, even a single one.
         quote! {
-            <Self as quest_hook::libil2cpp::Type>::class().invoke(#name_str, ( #( #args ),* ) )
+            let method = #root::__generic_methods::instantiate(
+                <Self as #type_trait>::class(),
+                #name_str,
+                &[ #( #signature ),* ],
+                &[ #( <#type_args as #type_trait>::class() ),* ],
+            )?;
+            method
+                .invoke(#this, ( #( #args, )* ))
+                .map_err(|exception| &*exception)
         }
     }
 
-    fn write_instance_body(&self, args: impl Iterator<Item = Ident>) -> TokenStream {
-        let name_str = &self.name;
+    /// Name for methods whose name has no characters that can be used in an identifier, like
+    /// `method_Void_String_3`
+    fn fallback_name(&self, position: usize, cx: &Context<'_>) -> String {
+        let mut parts = vec!["method".to_owned(), self.return_type.fallback_part(cx)];
+        parts.extend(
+            self.parameters
+                .iter()
+                .map(|p| p.parameter_type.fallback_part(cx)),
+        );
+        parts.push(position.to_string());
+        parts.join("_")
+    }
 
-        quote! {
-            self.invoke(#name_str, ( #( #args ),* ))
-        }
+    /// Property accessors are compiler-generated for auto-properties, but they are part of the API
+    fn is_compiler_generated(&self) -> bool {
+        !self.is_special_name
+            && compiler_generated::is_compiler_generated(&self.name, &self.attributes)
     }
 
-    fn write_tokens(&self, i: usize, types: &DllData) -> TokenStream {
-        let name = create_ident(&(self.name.clone() + "_" + &i.to_string()));
-        let param_names = self.parameters.iter().enumerate().map(|(i, p)| {
-            if p.name.is_empty() {
-                create_ident(&format!("_param{}", i))
-            } else {
-                create_ident(&p.name)
+    /// Whether all types in the signature of the method are available to generated code
+    fn is_emittable(&self, cx: &Context<'_>) -> Result<bool, ErrorKind> {
+        if !self.return_type.is_emitted(cx)? {
+            return Ok(false);
+        }
+        for parameter in &self.parameters {
+            if !parameter.parameter_type.is_emitted(cx)? {
+                return Ok(false);
             }
-        });
+        }
+        Ok(true)
+    }
+
+    /// Writes the method as `name`. `owner` is the C# name of the declaring type, and
+    /// `type_generics` are the generic parameters of the declaring type, whose names as well as
+    /// `reserved` can't be taken by the generic parameters of the method.
+    fn write_tokens(
+        &self,
+        name: &str,
+        owner: &str,
+        reserved: &[String],
+        type_generics: &HashMap<&str, TokenStream>,
+        cx: &Context<'_>,
+    ) -> Result<TokenStream, ErrorKind> {
+        let name = create_ident(name);
+
+        let mut generic_scope = Scope::new(
+            &cx.renames,
+            format!("the generic parameters of {}.{}", owner, self.name),
+        );
+        generic_scope.reserve_all(reserved);
+        generic_scope.reserve_all(type_generics.values().map(ToString::to_string));
+        let mut substitutions = type_generics.clone();
+        let mut type_args = Vec::new();
+        for generic in &self.generic_parameters {
+            let ident = create_ident(&generic_scope.allocate(&generic.name)).into_token_stream();
+            substitutions.insert(generic.name.as_str(), ident.clone());
+            type_args.push(ident);
+        }
+
+        // `method` is a local of the bodies of generic methods
+        let mut param_scope = Scope::new(
+            &cx.renames,
+            format!("the parameters of {}.{}", owner, self.name),
+        );
+        param_scope.reserve("method");
+        let param_names: Vec<Ident> = self
+            .parameters
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let name = if is_unnamable(&p.name) {
+                    format!("_param{}", i)
+                } else {
+                    p.name.clone()
+                };
+                create_ident(&param_scope.allocate(&name))
+            })
+            .collect();
         let param_types = self
             .parameters
             .iter()
-            .map(|p| p.parameter_type.write_instance_type(types, RefType::Ref));
+            .map(|p| {
+                p.parameter_type
+                    .write_instance_type(cx, RefType::Ref, &substitutions)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
         let generics = if !self.generic_parameters.is_empty() {
             let args = self
                 .generic_parameters
                 .iter()
-                .map(|tr| create_ident(&tr.name));
-            Some(quote! { < #( #args: quest_hook::libil2cpp::Type ),* > })
+                .map(|tr| tr.write_generic_param(cx, &substitutions))
+                .collect::<Result<Vec<_>, _>>()?;
+            Some(quote! { < #( #args ),* > })
         } else {
             None
         };
-        let doc = format!("Offset: {:0X}", self.offset);
+        let doc = format!(" Offset: {:0X}", self.offset);
         let is_instance = !self.specifiers.iter().any(|s| s == "static");
         let return_type = self.return_type.write_instance_type(
-            types,
+            cx,
             if is_instance {
                 RefType::Ref
             } else {
                 RefType::StaticRef
             },
-        );
+            &substitutions,
+        )?;
         let self_param = is_instance.then(|| quote! { &mut self, });
         // let all_params = iter::once(self_param).chain(quote! { #param_names: #param_types });
 
-        let args = param_names.clone();
-        let body = if is_instance {
-            self.write_instance_body(args)
+        // Only generic methods are invoked so far, through their runtime instantiation
+        let body = if self.generic_parameters.is_empty() {
+            quote! { unimplemented!() }
         } else {
-            self.write_static_body(args)
+            let args = param_names.iter().cloned();
+            self.write_generic_body(cx, is_instance, args, &type_args)
         };
         let exception_lifetime = (!is_instance).then(|| quote! { 'static });
+        let exception = cx.runtime(Backend::exception);
 
-        quote! {
+        Ok(quote! {
             #[doc = #doc]
             pub fn #name #generics (
                     #self_param
                     #( #param_names: #param_types ),*
-                ) -> Result<#return_type, & #exception_lifetime quest_hook::libil2cpp::Il2CppException>
+                ) -> Result<#return_type, & #exception_lifetime #exception>
             {
-                unimplemented!()
-                // #body
+                #body
             }
-        }
+        })
     }
 }
 
 impl TypeData {
-    fn phantom_data_fields<'a>(
-        &'a self,
-        types: &'a DllData,
-    ) -> impl Iterator<Item = TokenStream> + 'a {
+    fn phantom_data_names(&self) -> impl Iterator<Item = String> {
+        (0..self.this.generics.len()).map(|i| format!("__phantom_data_{}", i))
+    }
+
+    fn phantom_data_fields(
+        &self,
+        cx: &Context<'_>,
+        substitutions: &HashMap<&str, TokenStream>,
+    ) -> Result<Vec<TokenStream>, ErrorKind> {
         self.this
             .generics
             .iter()
-            .enumerate()
-            .map(move |(i, generic)| {
-                let name = create_ident(&format!("__phantom_data_{}", i));
-                let ty = generic.write_qualified_name(types);
-                quote! {
+            .zip(self.phantom_data_names())
+            .map(|(generic, name)| {
+                let name = create_ident(&name);
+                let ty = generic.write_substituted_name(cx, substitutions)?;
+                Ok(quote! {
                     #name: std::marker::PhantomData<*const #ty>
-                }
+                })
             })
+            .collect()
     }
 
     fn write_deref(
@@ -243,11 +749,15 @@ impl TypeData {
         name: &Ident,
         generics: &Option<TokenStream>,
         generics_unbounded: &Option<TokenStream>,
-        types: &DllData,
-    ) -> Option<TokenStream> {
-        let parent = self.parent.as_ref()?;
-        let super_type = parent.write_qualified_name(types);
-        Some(quote! {
+        cx: &Context<'_>,
+        substitutions: &HashMap<&str, TokenStream>,
+    ) -> Result<Option<TokenStream>, ErrorKind> {
+        let parent = match &self.parent {
+            Some(parent) => parent,
+            None => return Ok(None),
+        };
+        let super_type = parent.write_substituted_name(cx, substitutions)?;
+        Ok(Some(quote! {
             impl #generics std::ops::Deref for #name #generics_unbounded {
                 type Target = #super_type;
 
@@ -255,46 +765,433 @@ impl TypeData {
                     &self.super_
                 }
             }
+        }))
+    }
+
+    fn type_override<'a>(&self, cx: &Context<'a>) -> Option<&'a TypeOverride> {
+        cx.config.types.get(&self.cs_full_name(cx.types))
+    }
+
+    /// Looks up the entry of the type in one of the tables of the rename map
+    fn rename_entry<'a, T>(&self, map: &'a BTreeMap<String, T>, cx: &Context<'_>) -> Option<&'a T> {
+        RenameMap::for_type(map, self.type_def_index, &self.cs_full_name(cx.types))
+    }
+
+    /// The name given to the type by an override or the rename map
+    fn renamed<'a>(&self, cx: &Context<'a>) -> Option<&'a str> {
+        let type_override = self.type_override(cx).and_then(|o| o.rename.as_deref());
+        type_override.or_else(|| {
+            self.rename_entry(&cx.config.renames.types, cx)
+                .map(String::as_str)
         })
     }
 
-    fn full_name(&self, types: &DllData) -> Ident {
-        if let Some(nested_parent) = &self.this.declaring_type {
-            let name = nested_parent.full_name(types) + "_" + &self.this.name;
-            create_ident(&name)
+    /// Names of `fields`, which are fields of this type, allocated in `scope` without their
+    /// leading underscores. Fields without a usable name are named after their type and position
+    /// once all other fields are named.
+    fn field_names(
+        &self,
+        fields: &[Field],
+        case: Case,
+        scope: &mut Scope<'_>,
+        cx: &Context<'_>,
+    ) -> Vec<String> {
+        let renamed_fields = self.rename_entry(&cx.config.renames.fields, cx);
+        let names: Vec<Option<String>> = fields
+            .iter()
+            .map(
+                |field| match renamed_fields.and_then(|f| f.get(&field.name)) {
+                    Some(name) => Some(scope.allocate(name)),
+                    None => {
+                        let name = cx
+                            .compiler_generated_name(&field.name, false)
+                            .unwrap_or_else(|| field.name.clone());
+                        (!is_unnamable(&name))
+                            .then(|| scope.allocate(case.apply(&name).trim_start_matches('_')))
+                    }
+                },
+            )
+            .collect();
+        names
+            .into_iter()
+            .zip(fields)
+            .enumerate()
+            .map(|(i, (name, field))| {
+                name.unwrap_or_else(|| scope.allocate(&field.fallback_name(i, cx)))
+            })
+            .collect()
+    }
+
+    /// Name for types whose name has no characters that can be used in an identifier, like
+    /// `Class_1234`
+    fn fallback_name(&self) -> String {
+        format!("{:?}_{}", self.type_enum, self.type_def_index)
+    }
+
+    fn rust_name<'a>(&'a self, cx: &Context<'a>) -> Cow<'a, str> {
+        if let Some(name) = self.renamed(cx) {
+            return Cow::Borrowed(name);
+        }
+        let is_async = self
+            .implementing_interfaces
+            .iter()
+            .any(|interface| interface.name == "IAsyncStateMachine");
+        match cx.compiler_generated_name(&self.this.name, is_async) {
+            Some(name) => Cow::Owned(name),
+            None if is_unnamable(&self.this.name) => Cow::Owned(self.fallback_name()),
+            None => Cow::Borrowed(&self.this.name),
+        }
+    }
+
+    /// Whether the type was generated by the compiler, which types nested in such types are too
+    fn is_compiler_generated(&self, types: &DllData) -> bool {
+        compiler_generated::is_compiler_generated(&self.this.name, &self.attributes)
+            || self
+                .this
+                .declaring_type
+                .as_ref()
+                .and_then(|declaring_type| types.get(declaring_type))
+                .is_some_and(|declaring_type| declaring_type.is_compiler_generated(types))
+    }
+
+    /// The name the type is allocated its identifier for in its module
+    fn base_name(&self, cx: &Context<'_>) -> String {
+        if cx.config.output.nested_modules {
+            self.rust_name(cx).into_owned()
         } else {
-            create_ident(&self.this.name)
+            self.flattened_base_name(cx)
         }
     }
 
+    /// The name of nested types prefixed with the names of their declaring types, like
+    /// `Player_State`
+    fn flattened_base_name(&self, cx: &Context<'_>) -> String {
+        match &self.this.declaring_type {
+            Some(nested_parent) => nested_parent.full_name(cx) + "_" + &self.rust_name(cx),
+            None => self.rust_name(cx).into_owned(),
+        }
+    }
+
+    /// The modules of the namespace the type is emitted in. Nested types live in the namespace of
+    /// their outermost declaring type, and compiler-generated types can be moved to a module of
+    /// their own in it.
+    fn namespace_module_path(&self, cx: &Context<'_>) -> Vec<String> {
+        let namespace = match self.declaring_types(cx.types).first() {
+            Some(outermost) => &outermost.namespace,
+            None => &self.this.namespace,
+        };
+        // Segments can be empty, like in `A..B`, or obfuscated
+        let mut path: Vec<String> = namespace
+            .split_terminator('.')
+            .enumerate()
+            .map(|(position, part)| {
+                if is_unnamable(part) {
+                    format!("Namespace_{}", position)
+                } else {
+                    part.to_owned()
+                }
+            })
+            .collect();
+        if cx.config.filter.compiler_generated == CompilerGenerated::Module
+            && self.is_compiler_generated(cx.types)
+        {
+            path.push(COMPILER_GENERATED_MODULE.to_owned());
+        }
+        path
+    }
+
+    /// Names of the modules the type is emitted in when nested types are emitted in nested
+    /// modules
+    fn nested_module_path(&self, cx: &Context<'_>) -> Vec<String> {
+        if !cx.config.output.nested_modules {
+            return Vec::new();
+        }
+        self.declaring_types(cx.types)
+            .into_iter()
+            .map(|declaring_type| nested_module_name(&declaring_type.rust_name(cx)))
+            .collect()
+    }
+
+    /// The namespace modules and nested modules leading to the module the type is emitted in
+    fn module_path(&self, cx: &Context<'_>) -> Vec<String> {
+        let mut path = self.namespace_module_path(cx);
+        path.extend(self.nested_module_path(cx));
+        path
+    }
+
+    /// The identifier allocated for the type, which has the id `id`
+    fn full_name(&self, cx: &Context<'_>, id: usize) -> Result<Ident, ErrorKind> {
+        Ok(create_ident(cx.names.type_name(id)?))
+    }
+
+    fn flattened_name(&self, cx: &Context<'_>, id: usize) -> Result<Ident, ErrorKind> {
+        Ok(create_ident(cx.names.alias(id)?))
+    }
+
+    /// Identifiers of the generic parameters of the type, by their C# names
+    fn generic_names(
+        &self,
+        cx: &Context<'_>,
+        id: usize,
+    ) -> Result<HashMap<&str, TokenStream>, ErrorKind> {
+        let names = cx.names.generics(id)?;
+        Ok(self
+            .this
+            .generics
+            .iter()
+            .zip(names)
+            .map(|(generic, name)| {
+                (
+                    generic.name.as_str(),
+                    create_ident(name).into_token_stream(),
+                )
+            })
+            .collect())
+    }
+
+    fn generics_unbounded(
+        &self,
+        cx: &Context<'_>,
+        id: usize,
+    ) -> Result<Option<TokenStream>, ErrorKind> {
+        Ok(if !self.this.generics.is_empty() {
+            let args = cx.names.generics(id)?.iter().map(|name| create_ident(name));
+            Some(quote! { < #( #args ),* > })
+        } else {
+            None
+        })
+    }
+
+    /// Generic parameters of the struct itself, which are only bounded by the type trait. The
+    /// runtime's macros implement the type trait for any arguments implementing it, so the
+    /// translated constraints are bounds of the impls instead.
+    fn struct_generics(
+        &self,
+        cx: &Context<'_>,
+        id: usize,
+    ) -> Result<Option<TokenStream>, ErrorKind> {
+        Ok(if !self.this.generics.is_empty() {
+            let type_trait = cx.runtime(Backend::type_trait);
+            let args = cx.names.generics(id)?.iter().map(|name| create_ident(name));
+            Some(quote! { < #( #args: #type_trait ),* > })
+        } else {
+            None
+        })
+    }
+
+    /// Alias for a type emitted in nested modules under its flattened name
+    fn write_nested_alias(&self, cx: &Context<'_>, id: usize) -> Result<TokenStream, ErrorKind> {
+        let alias = self.flattened_name(cx, id)?;
+        let name = self.full_name(cx, id)?;
+        let modules = cx
+            .names
+            .module_path(&self.module_path(cx))
+            .into_iter()
+            .skip(self.namespace_module_path(cx).len())
+            .map(|module| create_ident(&module));
+        let generics = self.generics_unbounded(cx, id)?;
+
+        Ok(quote! {
+            pub type #alias #generics = #( #modules :: )* #name #generics;
+        })
+    }
+
     fn pass_by_ref(&self) -> bool {
         matches!(self.type_enum, TypeEnum::Class | TypeEnum::Interface)
     }
 
-    fn write_class(&self, types: &DllData, generics: &Option<TokenStream>, generics_unbounded: &Option<TokenStream>) -> TokenStream {
-        let name = self.full_name(types);
-        let fields = self
-            .instance_fields
-            .iter()
-            .map(|f| f.write_tokens(types))
-            .chain(self.phantom_data_fields(types));
-        let super_field = self.parent.as_ref().map(|parent| {
-            let super_ident = create_ident("super_");
-            let super_type = parent.write_qualified_name(types);
-            quote! {
-                #super_ident: #super_type,
+    /// All base classes and interfaces of the type, written in terms of its own generic
+    /// parameters, which are named by `substitutions`
+    fn supertypes(
+        &self,
+        cx: &Context<'_>,
+        substitutions: &HashMap<&str, TokenStream>,
+    ) -> Result<Vec<TokenStream>, ErrorKind> {
+        let mut supertypes = Vec::new();
+        let mut seen = HashSet::new();
+        let mut pending = vec![(self, substitutions.clone())];
+        while let Some((ty, substitutions)) = pending.pop() {
+            for super_ref in ty.parent.iter().chain(&ty.implementing_interfaces) {
+                if super_ref.type_id < 0 || !super_ref.is_emitted(cx)? {
+                    continue;
+                }
+                let super_name = super_ref.write_substituted_name(cx, &substitutions)?;
+                if !seen.insert(super_name.to_string()) {
+                    continue;
+                }
+
+                let super_ty = cx.ty(super_ref)?;
+                let mut super_substitutions = HashMap::new();
+                for (param, arg) in super_ty.this.generics.iter().zip(&super_ref.generics) {
+                    let arg = arg.write_substituted_name(cx, &substitutions)?;
+                    super_substitutions.insert(param.name.as_str(), arg);
+                }
+                pending.push((super_ty, super_substitutions));
+                supertypes.push(super_name);
             }
-        });
-        let methods = self
-            .methods
-            .iter()
-            .enumerate()
-            .map(|(i, m)| m.write_tokens(i, types));
-        let deref = self.write_deref(&name, generics, generics_unbounded, types);
+        }
+        Ok(supertypes)
+    }
 
-        quote! {
+    /// Implementations of the traits in `__bounds` which generic constraints are translated to
+    fn write_bound_impls(
+        &self,
+        cx: &Context<'_>,
+        self_ty: &TokenStream,
+        generics: &Option<TokenStream>,
+        substitutions: &HashMap<&str, TokenStream>,
+    ) -> Result<Vec<TokenStream>, ErrorKind> {
+        let kind = if self.pass_by_ref() {
+            quote! { ReferenceType }
+        } else {
+            quote! { ValueType }
+        };
+        let root = cx.root();
+        let kind_impl = quote! {
+            unsafe impl #generics #root::__bounds::#kind for #self_ty {}
+        };
+        let subtype_impls = std::iter::once(self_ty.clone())
+            .chain(self.supertypes(cx, substitutions)?)
+            .map(|supertype| {
+                quote! {
+                    unsafe impl #generics #root::__bounds::SubtypeOf<#supertype> for #self_ty {}
+                }
+            });
+
+        Ok(std::iter::once(kind_impl).chain(subtype_impls).collect())
+    }
+
+    /// Overloads get distinct names by suffixing them with their index among the methods of the
+    /// same name, so adding a method does not rename unrelated ones. Methods without a usable
+    /// name are named after their signature and position once all other methods are named.
+    /// Methods using types which are not generated are left out.
+    fn write_methods(
+        &self,
+        cx: &Context<'_>,
+        id: usize,
+        substitutions: &HashMap<&str, TokenStream>,
+    ) -> Result<Vec<TokenStream>, GenerateError> {
+        let type_override = self.type_override(cx);
+        let renamed_methods = self.rename_entry(&cx.config.renames.methods, cx);
+        let method_override = |method: &Method| {
+            type_override.and_then(|type_override| type_override.methods.get(&method.name))
+        };
+
+        let owner = self.cs_full_name(cx.types);
+        let mut scope = Scope::new(&cx.renames, format!("the methods of {}", owner));
+        let mut overloads = HashMap::new();
+        let method_error = |method: &Method, error| {
+            GenerateError::for_member(&owner, MemberKind::Method, &method.name, error)
+        };
+        let mut emitted = Vec::new();
+        for (position, method) in self.methods.iter().enumerate() {
+            let overload = overloads.entry(method.name.as_str()).or_insert(0);
+            let i = *overload;
+            *overload += 1;
+            if method_override(method).is_some_and(|method_override| method_override.skip)
+                || (cx.config.filter.compiler_generated == CompilerGenerated::Skip
+                    && method.is_compiler_generated())
+            {
+                continue;
+            }
+            match method.is_emittable(cx) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(error) => {
+                    cx.skip(method_error(method, error))?;
+                    continue;
+                }
+            }
+
+            let renamed = method_override(method)
+                .and_then(|o| o.rename.as_ref())
+                .or_else(|| renamed_methods.and_then(|methods| methods.get(&method.name)));
+            let name = match renamed {
+                Some(name) => Some(format!("{}_{}", name, i)),
+                None => {
+                    let name = cx
+                        .compiler_generated_name(&method.name, false)
+                        .unwrap_or_else(|| method.name.clone());
+                    (!is_unnamable(&name))
+                        .then(|| format!("{}_{}", cx.config.naming.methods.apply(&name), i))
+                }
+            };
+            emitted.push((position, method, name.map(|name| scope.allocate(&name))));
+        }
+
+        // Generic parameters of methods can't shadow the type or its generic parameters
+        let mut reserved = cx.reserved_names();
+        let name = self
+            .full_name(cx, id)
+            .map_err(|error| GenerateError::for_type(&owner, error))?;
+        reserved.push(name.to_string());
+        let mut methods = Vec::new();
+        for (position, method, name) in emitted {
+            let name = name.unwrap_or_else(|| scope.allocate(&method.fallback_name(position, cx)));
+            match method.write_tokens(&name, &owner, &reserved, substitutions, cx) {
+                Ok(tokens) => methods.push(tokens),
+                Err(error) => cx.skip(method_error(method, error))?,
+            }
+        }
+        Ok(methods)
+    }
+
+    fn write_class(
+        &self,
+        cx: &Context<'_>,
+        id: usize,
+        generics: &Option<TokenStream>,
+        generics_unbounded: &Option<TokenStream>,
+    ) -> Result<TokenStream, GenerateError> {
+        let full_name = self.cs_full_name(cx.types);
+        let type_error = |error| GenerateError::for_type(&full_name, error);
+        let name = self.full_name(cx, id).map_err(type_error)?;
+        let substitutions = self.generic_names(cx, id).map_err(type_error)?;
+        let struct_generics = self.struct_generics(cx, id).map_err(type_error)?;
+        let mut field_scope = Scope::new(&cx.renames, format!("the fields of {}", full_name));
+        if self.parent.is_some() {
+            field_scope.reserve("super_");
+        }
+        field_scope.reserve_all(self.phantom_data_names());
+        let field_names = self.field_names(
+            &self.instance_fields,
+            cx.config.naming.fields,
+            &mut field_scope,
+            cx,
+        );
+        let mut fields = Vec::new();
+        for (field, field_name) in self.instance_fields.iter().zip(&field_names) {
+            let tokens = field
+                .write_tokens(field_name, cx, &substitutions)
+                .map_err(|error| {
+                    GenerateError::for_member(&full_name, MemberKind::Field, &field.name, error)
+                })?;
+            fields.push(tokens);
+        }
+        fields.extend(
+            self.phantom_data_fields(cx, &substitutions)
+                .map_err(type_error)?,
+        );
+        let super_field = match &self.parent {
+            Some(parent) => {
+                let super_ident = create_ident("super_");
+                let super_type = parent
+                    .write_substituted_name(cx, &substitutions)
+                    .map_err(type_error)?;
+                Some(quote! {
+                    #super_ident: #super_type,
+                })
+            }
+            None => None,
+        };
+        let methods = self.write_methods(cx, id, &substitutions)?;
+        let deref = self
+            .write_deref(&name, generics, generics_unbounded, cx, &substitutions)
+            .map_err(type_error)?;
+
+        Ok(quote! {
             #[repr(C)]
-            pub struct #name #generics {
+            pub struct #name #struct_generics {
                 #super_field
                 #( #fields ),*
             }
@@ -304,49 +1201,88 @@ impl TypeData {
             }
 
             #deref
-        }
+        })
     }
 
-    fn write_interface(&self, types: &DllData, generics: &Option<TokenStream>, generics_unbounded: &Option<TokenStream>) -> TokenStream {
-        let name = self.full_name(types);
-        let methods = self
-            .methods
-            .iter()
-            .enumerate()
-            .map(|(i, m)| m.write_tokens(i, types));
-        let fields = self.phantom_data_fields(types);
+    fn write_interface(
+        &self,
+        cx: &Context<'_>,
+        id: usize,
+        generics: &Option<TokenStream>,
+        generics_unbounded: &Option<TokenStream>,
+    ) -> Result<TokenStream, GenerateError> {
+        let type_error = |error| GenerateError::for_type(self.cs_full_name(cx.types), error);
+        let name = self.full_name(cx, id).map_err(type_error)?;
+        let substitutions = self.generic_names(cx, id).map_err(type_error)?;
+        let struct_generics = self.struct_generics(cx, id).map_err(type_error)?;
+        let methods = self.write_methods(cx, id, &substitutions)?;
+        let fields = self
+            .phantom_data_fields(cx, &substitutions)
+            .map_err(type_error)?;
 
-        quote! {
-            pub struct #name #generics {
+        Ok(quote! {
+            pub struct #name #struct_generics {
                 #( #fields ),*
             }
 
             impl #generics #name #generics_unbounded {
                 #( #methods )*
             }
-        }
+        })
     }
 
-    fn write_enum(&self, types: &DllData, generics: &Option<TokenStream>, generics_unbounded: &Option<TokenStream>) -> TokenStream {
-        let name = self.full_name(types);
-        let fields = self.phantom_data_fields(types);
-        let ty = self.instance_fields[0]
+    fn write_enum(
+        &self,
+        cx: &Context<'_>,
+        id: usize,
+        generics: &Option<TokenStream>,
+        generics_unbounded: &Option<TokenStream>,
+    ) -> Result<TokenStream, GenerateError> {
+        let full_name = self.cs_full_name(cx.types);
+        let type_error = |error| GenerateError::for_type(&full_name, error);
+        let name = self.full_name(cx, id).map_err(type_error)?;
+        let substitutions = self.generic_names(cx, id).map_err(type_error)?;
+        let struct_generics = self.struct_generics(cx, id).map_err(type_error)?;
+        let fields = self
+            .phantom_data_fields(cx, &substitutions)
+            .map_err(type_error)?;
+        let value_field = self
+            .instance_fields
+            .first()
+            .ok_or_else(|| type_error(ErrorKind::MissingEnumValue))?;
+        let ty = value_field
             .field_type
-            .write_instance_type(types, RefType::Pointer);
-        let variants = self
-            .static_fields
-            .iter()
-            .map(|f| {
-                let ident = create_ident(&f.name);
-                let val = f.constant.as_ref().unwrap().parse::<TokenStream>().unwrap();
-                quote! {
-                    const #ident: #ty = #val;
+            .write_instance_type(cx, RefType::Pointer, &substitutions)
+            .map_err(|error| {
+                GenerateError::for_member(&full_name, MemberKind::Field, &value_field.name, error)
+            })?;
+        let mut variant_scope = Scope::new(&cx.renames, format!("the constants of {}", full_name));
+        let variant_names =
+            self.field_names(&self.static_fields, Case::Preserve, &mut variant_scope, cx);
+        let mut variants = Vec::new();
+        for (f, name) in self.static_fields.iter().zip(&variant_names) {
+            let ident = create_ident(name);
+            let val = match f.constant.as_ref().map(|c| c.parse::<TokenStream>()) {
+                Some(Ok(val)) => val,
+                _ => {
+                    let error = ErrorKind::InvalidConstant(f.constant.clone());
+                    cx.skip(GenerateError::for_member(
+                        &full_name,
+                        MemberKind::Constant,
+                        &f.name,
+                        error,
+                    ))?;
+                    continue;
                 }
+            };
+            variants.push(quote! {
+                const #ident: #ty = #val;
             });
+        }
 
-        quote! {
+        Ok(quote! {
             #[repr(C)]
-            pub struct #name #generics {
+            pub struct #name #struct_generics {
                 value: #ty,
                 #( #fields ),*
             }
@@ -354,117 +1290,789 @@ impl TypeData {
             impl #generics #name #generics_unbounded {
                 #( #variants )*
             }
-        }
+        })
     }
 
-    fn write_tokens(&self, types: &DllData) -> TokenStream {
+    /// Implementation of the type trait of the runtime, which looks up the il2cpp class
+    fn write_type_trait_impl(
+        &self,
+        cx: &Context<'_>,
+        id: usize,
+        substitutions: &HashMap<&str, TokenStream>,
+    ) -> Result<TokenStream, ErrorKind> {
         let name_lit = &self.this.name;
         let namespace_lit = &self.this.namespace;
-        let name = self.full_name(types);
+        let name = self.full_name(cx, id)?;
+        let generics_unbounded = self.generics_unbounded(cx, id)?;
 
-        let generics = if !self.this.generics.is_empty() {
-            let args = self.this.generics.iter().map(|tr| create_ident(&tr.name));
-            Some(quote! { < #( #args: quest_hook::libil2cpp::Type ),* > })
+        let runtime_crate = cx
+            .config
+            .backend
+            .crate_path()
+            .parse::<TokenStream>()
+            .unwrap();
+        let is_value_type = matches!(self.type_enum, TypeEnum::Struct | TypeEnum::Enum);
+
+        // Nested classes have an empty namespace and can only be found through their declaring
+        // class, which takes the leading generic parameters of the nested class. The runtime's
+        // macros look classes up by namespace and name, so the impl is written out instead.
+        if let Some(declaring_type) = &self.this.declaring_type {
+            let mut declaring_type = declaring_type.clone();
+            let generic_count = cx.ty(&declaring_type)?.this.generics.len();
+            declaring_type.generics = self
+                .this
+                .generics
+                .get(..generic_count)
+                .ok_or(ErrorKind::MissingDeclaringGenerics {
+                    expected: generic_count,
+                    found: self.this.generics.len(),
+                })?
+                .to_vec();
+            let declaring_ty = declaring_type.write_substituted_name(cx, substitutions)?;
+            let root = cx.root();
+            let type_trait = cx.runtime(Backend::type_trait);
+            let class = cx.runtime(Backend::class);
+            let struct_generics = self.struct_generics(cx, id)?;
+            let (held, held_raw) = if is_value_type {
+                (quote! { Self }, quote! { Self })
+            } else {
+                (quote! { Option<&'a mut Self> }, quote! { *mut Self })
+            };
+            // Nested classes of generic types are generic definitions
+            let inflate = if !self.this.generics.is_empty() {
+                let args = cx.names.generics(id)?.iter().map(|name| create_ident(name));
+                Some(quote! {
+                    .and_then(|definition| #root::__nested_classes::inflate(definition, &[ #( <#args as #type_trait>::class() ),* ]))
+                })
+            } else {
+                None
+            };
+            return Ok(quote! {
+                unsafe impl #struct_generics #type_trait for #name #generics_unbounded {
+                    type Held<'a> = #held;
+                    type HeldRaw = #held_raw;
+
+                    fn class() -> &'static #class {
+                        #root::__nested_classes::find::<#declaring_ty>(#name_lit)
+                            #inflate
+                            .unwrap_or_else(|error| panic!("{}", error))
+                    }
+                }
+            });
+        }
+
+        Ok(if is_value_type {
+            let value_type_macro = cx.runtime(Backend::value_type_macro);
+            quote! {
+                #value_type_macro!(in #runtime_crate for #name #generics_unbounded => #namespace_lit . #name_lit #generics_unbounded );
+            }
         } else {
-            None
-        };
-        let generics_unbounded = if !self.this.generics.is_empty() {
-            let args = self.this.generics.iter().map(|tr| create_ident(&tr.name));
+            let reference_type_macro = cx.runtime(Backend::reference_type_macro);
+            quote! {
+                #reference_type_macro!(in #runtime_crate for #name #generics_unbounded => #namespace_lit . #name_lit #generics_unbounded );
+            }
+        })
+    }
+
+    fn write_tokens(&self, cx: &Context<'_>, id: usize) -> Result<TokenStream, GenerateError> {
+        let type_error = |error| GenerateError::for_type(self.cs_full_name(cx.types), error);
+        let name = self.full_name(cx, id).map_err(type_error)?;
+        let substitutions = self.generic_names(cx, id).map_err(type_error)?;
+
+        let generics = if !self.this.generics.is_empty() {
+            let args = self
+                .this
+                .generics
+                .iter()
+                .map(|tr| tr.write_generic_param(cx, &substitutions))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(type_error)?;
             Some(quote! { < #( #args ),* > })
         } else {
             None
         };
+        let generics_unbounded = self.generics_unbounded(cx, id).map_err(type_error)?;
 
         let ty = match self.type_enum {
-            TypeEnum::Class | TypeEnum::Struct => self.write_class(types, &generics, &generics_unbounded),
-            TypeEnum::Enum => self.write_enum(types, &generics, &generics_unbounded),
-            TypeEnum::Interface => self.write_interface(types, &generics, &generics_unbounded),
-        };
-
-        let ty_trait_impls = if matches!(self.type_enum, TypeEnum::Struct | TypeEnum::Enum) {
-            quote! {
-                quest_hook::libil2cpp::unsafe_impl_value_type!(in quest_hook::libil2cpp for #name #generics_unbounded => #namespace_lit . #name_lit #generics_unbounded );
-            }
-        } else {
-            quote! {
-                quest_hook::libil2cpp::unsafe_impl_reference_type!(in quest_hook::libil2cpp for #name #generics_unbounded => #namespace_lit . #name_lit #generics_unbounded );
+            TypeEnum::Class | TypeEnum::Struct => {
+                self.write_class(cx, id, &generics, &generics_unbounded)?
             }
+            TypeEnum::Enum => self.write_enum(cx, id, &generics, &generics_unbounded)?,
+            TypeEnum::Interface => self.write_interface(cx, id, &generics, &generics_unbounded)?,
+            TypeEnum::Unknown => return Err(type_error(ErrorKind::UnknownTypeKind)),
         };
+        let ty_trait_impls = self
+            .write_type_trait_impl(cx, id, &substitutions)
+            .map_err(type_error)?;
 
-        quote! {
+        let self_ty = quote! { #name #generics_unbounded };
+        let bound_impls = self
+            .write_bound_impls(cx, &self_ty, &generics, &substitutions)
+            .map_err(type_error)?;
+
+        Ok(quote! {
             #ty
 
             #ty_trait_impls
-        }
+
+            #( #bound_impls )*
+        })
+    }
+
+    /// Opaque stand-in for a type which can't be generated, so that code referring to it still
+    /// compiles. Stubs have no fields, so value types containing them don't have the right
+    /// layout.
+    fn write_stub(&self, cx: &Context<'_>, id: usize) -> Result<TokenStream, ErrorKind> {
+        let name = self.full_name(cx, id)?;
+        let generics = self.generics_unbounded(cx, id)?;
+        let params = cx
+            .names
+            .generics(id)?
+            .iter()
+            .map(|param| create_ident(param));
+        let ty_trait_impls = self
+            .write_type_trait_impl(cx, id, &self.generic_names(cx, id)?)
+            .ok();
+
+        Ok(quote! {
+            /// Stub for a type which could not be generated
+            #[repr(C)]
+            pub struct #name #generics {
+                __stub: std::marker::PhantomData<( #( *const #params, )* )>,
+            }
+
+            #ty_trait_impls
+        })
     }
 }
 
 #[derive(Default)]
 struct Module<'a> {
-    children: HashMap<String, Module<'a>>,
-    types: Vec<&'a TypeData>,
+    children: BTreeMap<String, Module<'a>>,
+    /// Types of the module by their ids, which are their indices in the dump
+    types: Vec<(usize, &'a TypeData)>,
+    /// Nested types emitted in child modules which get an alias in this module
+    nested_aliases: Vec<(usize, &'a TypeData)>,
 }
 
 impl DllData {
-    fn find_type(&self, namespace: &str, name: &str) -> i32 {
+    fn find_type(&self, namespace: &str, name: &str) -> Option<i32> {
         self.types
             .iter()
             .position(|ty| ty.this.namespace == namespace && ty.this.name == name)
-            .unwrap() as i32
+            .map(|id| id as i32)
     }
 
-    pub fn write_tokens(&self) -> TokenStream {
+    /// Finds a type by the name it is referred to by in the config
+    fn find_type_by_full_name(&self, full_name: &str) -> Option<i32> {
+        self.types
+            .iter()
+            .position(|ty| ty.cs_full_name(self) == full_name)
+            .map(|id| id as i32)
+    }
+
+    /// Checks that the type and method overrides refer to existing types and methods
+    fn check_overrides(&self, config: &Config) -> Result<()> {
+        for (full_name, type_override) in &config.types {
+            let id = self
+                .find_type_by_full_name(full_name)
+                .with_context(|| format!("`types.\"{}\"`: the type does not exist", full_name))?;
+            let ty = &self.types[id as usize];
+            for method in type_override.methods.keys() {
+                if !ty.methods.iter().any(|m| &m.name == method) {
+                    bail!(
+                        "`types.\"{}\".methods.{}`: the type has no method called `{}`",
+                        full_name,
+                        method,
+                        method
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Selects the types to generate from the filter. Base classes, declaring types and the types
+    /// of instance fields are needed for the layout of a type, so they are always generated with
+    /// it. Other dependencies are only generated if they are not skipped.
+    fn select_types(&self, config: &Config) -> Vec<bool> {
+        let filter = &config.filter;
+        let skipped: Vec<bool> = self
+            .types
+            .iter()
+            .map(|ty| {
+                config
+                    .types
+                    .get(&ty.cs_full_name(self))
+                    .is_some_and(|o| o.skip)
+                    || (filter.compiler_generated == CompilerGenerated::Skip
+                        && ty.is_compiler_generated(self))
+            })
+            .collect();
+        let mut pending = Vec::new();
+        for (id, ty) in self.types.iter().enumerate() {
+            let full_name = ty.cs_full_name(self);
+            let matches = |patterns: &[String]| {
+                patterns
+                    .iter()
+                    .any(|pattern| matches_pattern(pattern, &full_name))
+            };
+            if (filter.include.is_empty() || matches(&filter.include))
+                && !matches(&filter.exclude)
+                && !skipped[id]
+            {
+                pending.push(id);
+            }
+        }
+
+        let mut selected = vec![false; self.types.len()];
+        while let Some(id) = pending.pop() {
+            // Arrays and pointers are written as runtime types, the dump only lists them to give
+            // their element types
+            if self.types[id].this.element_type.is_some() {
+                continue;
+            }
+            if std::mem::replace(&mut selected[id], true) {
+                continue;
+            }
+            let ty = &self.types[id];
+            let layout = ty
+                .parent
+                .iter()
+                .chain(&ty.this.declaring_type)
+                .chain(ty.instance_fields.iter().map(|f| &f.field_type));
+            for dependency in layout {
+                dependency.collect_type_ids(self, &mut pending);
+            }
+            if filter.dependencies {
+                let mut dependencies: Vec<&TypeRef> = Vec::new();
+                dependencies.extend(&ty.implementing_interfaces);
+                dependencies.extend(&ty.this.generic_parameter_constraints);
+                for generic in &ty.this.generics {
+                    dependencies.extend(&generic.generic_parameter_constraints);
+                }
+                for method in &ty.methods {
+                    dependencies.push(&method.return_type);
+                    dependencies.extend(method.parameters.iter().map(|p| &p.parameter_type));
+                    for generic in &method.generic_parameters {
+                        dependencies.extend(&generic.generic_parameter_constraints);
+                    }
+                }
+                let mut ids = Vec::new();
+                for dependency in dependencies {
+                    dependency.collect_type_ids(self, &mut ids);
+                }
+                pending.extend(ids.into_iter().filter(|&id| !skipped[id]));
+            }
+        }
+        selected
+    }
+
+    pub(crate) fn write_tokens(&self, config: &Config) -> Result<Generated<TokenStream>> {
         // println!("{}", serde_json::to_string(&self.types[19]).unwrap());
 
-        let replacements = ReplacementTypes {
-            single: self.find_type("System", "Single"),
-            double: self.find_type("System", "Double"),
-            void: self.find_type("System", "Void"),
-            char: self.find_type("System", "Char"),
-            int16: self.find_type("System", "Int16"),
-            int32: self.find_type("System", "Int32"),
-            int64: self.find_type("System", "Int64"),
-            uint16: self.find_type("System", "UInt16"),
-            uint32: self.find_type("System", "UInt32"),
-            uint64: self.find_type("System", "UInt64"),
-            byte: self.find_type("System", "Byte"),
-            sbyte: self.find_type("System", "SByte"),
-            boolean: self.find_type("System", "Boolean"),
-            object: self.find_type("System", "Object"),
-            string: self.find_type("System", "String"),
+        self.check_overrides(config)?;
+        let mut cx = Context {
+            types: self,
+            config,
+            replacements: ReplacementTypes::new(self, config)?,
+            emitted_types: self.select_types(config),
+            names: Names::default(),
+            skipped: RefCell::new(Vec::new()),
+            renames: RefCell::new(Vec::new()),
         };
-        REPLACEMENT_TYPES.set(replacements).unwrap();
-
         let mut global_module = Module::default();
-        for ty in &self.types {
-            let namespace = ty.this.namespace.split_terminator('.');
+        for (id, (ty, _)) in self
+            .types
+            .iter()
+            .zip(&cx.emitted_types)
+            .enumerate()
+            .filter(|(_, (_, &emitted))| emitted)
+        {
             let mut module = &mut global_module;
-            for part in namespace {
-                module = module.children.entry(part.to_owned()).or_default();
+            for part in ty.namespace_module_path(&cx) {
+                module = module.children.entry(part).or_default();
             }
-            module.types.push(ty);
+            let nested_modules = ty.nested_module_path(&cx);
+            if !nested_modules.is_empty() {
+                module.nested_aliases.push((id, ty));
+                for part in nested_modules {
+                    module = module.children.entry(part).or_default();
+                }
+            }
+            module.types.push((id, ty));
         }
 
-        let code = global_module.write_tokens(self);
+        let mut names = Names::default();
+        global_module.allocate_names(&cx, &mut Vec::new(), &cx.reserved_names(), &mut names);
+        cx.names = names;
+        let cx = &cx;
 
-        quote! {
-            #![allow(warnings)]
-            #![feature(arbitrary_enum_discriminant, generic_associated_types, once_cell)]
+        // Replaced types need to satisfy the same constraints as the types they stand for, which
+        // is only possible for replacements implementing the type trait. Some types share a
+        // replacement, so identical implementations are only written once.
+        let mut seen_impls = HashSet::new();
+        let mut replacement_bound_impls = Vec::new();
+        for (id, replacement) in cx.replacements.bounded() {
+            let ty = &self.types[id as usize];
+            match ty.write_bound_impls(cx, &replacement, &None, &HashMap::new()) {
+                Ok(bound_impls) => replacement_bound_impls.extend(
+                    bound_impls
+                        .into_iter()
+                        .filter(|bound_impl| seen_impls.insert(bound_impl.to_string())),
+                ),
+                Err(error) => cx.skip(GenerateError::for_type(ty.cs_full_name(self), error))?,
+            }
+        }
+
+        let code = global_module.write_tokens(cx, &mut Vec::new())?;
+
+        let type_trait = cx.runtime(Backend::type_trait);
+        let object = cx.runtime(Backend::object);
+        let class = cx.runtime(Backend::class);
+        let method_info = cx.runtime(Backend::method_info);
+        let exception = cx.runtime(Backend::exception);
+        let raw = cx.runtime(Backend::raw);
+
+        let code = quote! {
+            /// Traits that C# generic parameter constraints are translated to
+            pub mod __bounds {
+                /// Implemented by all value types, corresponds to the `struct` constraint
+                pub unsafe trait ValueType: #type_trait {}
+
+                /// Implemented by all reference types, corresponds to the `class` constraint
+                pub unsafe trait ReferenceType: #type_trait {}
+
+                /// Implemented by a type for itself, all of its base classes and all interfaces it
+                /// implements
+                pub unsafe trait SubtypeOf<T: ?Sized> {}
+            }
+
+            #( #replacement_bound_impls )*
+
+            /// Arrays with more than one dimension
+            pub mod __arrays {
+                use #object as Il2CppObject;
+
+                #[repr(C)]
+                pub struct ArrayBounds {
+                    pub length: usize,
+                    pub lower_bound: i32,
+                }
+
+                /// An array of rank `RANK`, stored in row-major order
+                #[repr(C)]
+                pub struct MultidimensionalArray<T, const RANK: usize> {
+                    object: Il2CppObject,
+                    bounds: *const [ArrayBounds; RANK],
+                    max_length: usize,
+                    values: [T; 0],
+                }
+
+                impl<T, const RANK: usize> MultidimensionalArray<T, RANK> {
+                    pub fn bounds(&self) -> &[ArrayBounds; RANK] {
+                        unsafe { &*self.bounds }
+                    }
+
+                    pub fn len(&self) -> usize {
+                        self.max_length
+                    }
+
+                    pub fn as_slice(&self) -> &[T] {
+                        unsafe { std::slice::from_raw_parts(self.values.as_ptr(), self.max_length) }
+                    }
+
+                    pub fn as_mut_slice(&mut self) -> &mut [T] {
+                        unsafe { std::slice::from_raw_parts_mut(self.values.as_mut_ptr(), self.max_length) }
+                    }
+
+                    fn flat_index(&self, indices: [usize; RANK]) -> Option<usize> {
+                        let mut index = 0;
+                        for (i, bounds) in indices.iter().zip(self.bounds()) {
+                            if *i >= bounds.length {
+                                return None;
+                            }
+                            index = index * bounds.length + i;
+                        }
+                        Some(index)
+                    }
+
+                    /// Returns the element at `indices`, which are relative to the lower bounds
+                    pub fn get(&self, indices: [usize; RANK]) -> Option<&T> {
+                        let index = self.flat_index(indices)?;
+                        self.as_slice().get(index)
+                    }
+
+                    /// Returns the element at `indices`, which are relative to the lower bounds
+                    pub fn get_mut(&mut self, indices: [usize; RANK]) -> Option<&mut T> {
+                        let index = self.flat_index(indices)?;
+                        self.as_mut_slice().get_mut(index)
+                    }
+                }
+            }
+
+            /// Lookup of nested classes, which il2cpp does not find by namespace and name
+            pub mod __nested_classes {
+                use #raw as raw;
+                use #class as Il2CppClass;
+                use #type_trait as Type;
+                use std::collections::HashMap;
+                use std::ffi::CStr;
+                use std::sync::OnceLock as OnceCell;
+                use std::sync::Mutex;
+
+                static CLASSES: OnceCell<Mutex<HashMap<(usize, &'static str), usize>>> = OnceCell::new();
+
+                /// Finds the class called `name` nested in `D`. If `D` is nested itself, its class is
+                /// found the same way, so the whole chain of declaring types is walked. Nested classes
+                /// of generic types are generic definitions, which the caller inflates. Classes are
+                /// only looked up once per declaring class.
+                pub fn find<D: Type>(name: &'static str) -> Result<&'static Il2CppClass, String> {
+                    let declaring_class = D::class();
+                    let key = (declaring_class as *const _ as usize, name);
+                    let classes = CLASSES.get_or_init(Default::default);
+                    if let Some(class) = classes.lock().unwrap().get(&key) {
+                        return Ok(unsafe { &*(*class as *const Il2CppClass) });
+                    }
+
+                    let mut iter = std::ptr::null_mut();
+                    let class = loop {
+                        let nested = unsafe { raw::il2cpp_class_get_nested_types(declaring_class as *const _ as *mut _, &mut iter) };
+                        if nested.is_null() {
+                            let declaring_name = unsafe { CStr::from_ptr(raw::il2cpp_class_get_name(declaring_class as *const _ as *mut _)) };
+                            return Err(format!("nested class {} not found in {}", name, declaring_name.to_string_lossy()));
+                        }
+                        let nested_name = unsafe { CStr::from_ptr(raw::il2cpp_class_get_name(nested)) };
+                        if nested_name.to_bytes() == name.as_bytes() {
+                            break unsafe { &*(nested as *const Il2CppClass) };
+                        }
+                    };
+                    classes.lock().unwrap().insert(key, class as *const _ as usize);
+                    Ok(class)
+                }
+
+                static INFLATED: OnceCell<Mutex<HashMap<(usize, Vec<usize>), usize>>> = OnceCell::new();
+
+                /// Inflates the generic class definition `definition` with `type_args`. il2cpp does
+                /// not export class inflation, so it is done through reflection by calling
+                /// `MakeGenericType`. Every instantiation is only created once.
+                pub fn inflate(
+                    definition: &'static Il2CppClass,
+                    type_args: &[&'static Il2CppClass],
+                ) -> Result<&'static Il2CppClass, String> {
+                    let key = (
+                        definition as *const _ as usize,
+                        type_args.iter().map(|arg| *arg as *const _ as usize).collect::<Vec<_>>(),
+                    );
+                    let inflated = INFLATED.get_or_init(Default::default);
+                    if let Some(class) = inflated.lock().unwrap().get(&key) {
+                        return Ok(unsafe { &*(*class as *const Il2CppClass) });
+                    }
+
+                    // Inflation runs managed code, so the lock is not held meanwhile
+                    let class = unsafe {
+                        let definition_object = raw::il2cpp_type_get_object(raw::il2cpp_class_get_type(definition));
+                        let inflated_object = super::__generic_methods::invoke_reflection(
+                            definition_object as _,
+                            b"MakeGenericType\0",
+                            &mut [super::__generic_methods::type_objects(type_args)],
+                        )
+                        .map_err(|_| {
+                            let name = CStr::from_ptr(raw::il2cpp_class_get_name(definition as *const _ as *mut _));
+                            format!("generic class {} could not be inflated", name.to_string_lossy())
+                        })?;
+                        &*(raw::il2cpp_class_from_system_type(inflated_object as _) as *const Il2CppClass)
+                    };
+                    inflated.lock().unwrap().insert(key, class as *const _ as usize);
+                    Ok(class)
+                }
+            }
+
+            /// Runtime instantiation of generic methods
+            pub mod __generic_methods {
+                use #raw as raw;
+                use #class as Il2CppClass;
+                use #exception as Il2CppException;
+                use #object as Il2CppObject;
+                use #method_info as MethodInfo;
+                use std::collections::HashMap;
+                use std::ffi::{CStr, CString};
+                use std::sync::OnceLock as OnceCell;
+                use std::sync::Mutex;
+
+                /// Declaring class, name and parameter type names of a generic method definition,
+                /// and the classes of the type arguments it is instantiated with
+                type Instantiation = (usize, &'static str, &'static [&'static str], Vec<usize>);
+
+                static INSTANTIATIONS: OnceCell<Mutex<HashMap<Instantiation, &'static MethodInfo>>> =
+                    OnceCell::new();
+
+                /// Returns the generic method `name` of `class` inflated with `type_args`.
+                /// `signature` holds the names of the parameter types as `il2cpp_type_get_name`
+                /// formats them and picks the overload. Every instantiation is only created once.
+                pub fn instantiate(
+                    class: &'static Il2CppClass,
+                    name: &'static str,
+                    signature: &'static [&'static str],
+                    type_args: &[&'static Il2CppClass],
+                ) -> Result<&'static MethodInfo, &'static Il2CppException> {
+                    let key = (
+                        class as *const _ as usize,
+                        name,
+                        signature,
+                        type_args.iter().map(|arg| *arg as *const _ as usize).collect(),
+                    );
+                    let instantiations = INSTANTIATIONS.get_or_init(Default::default);
+                    if let Some(method) = instantiations.lock().unwrap().get(&key) {
+                        return Ok(*method);
+                    }
+                    // Inflation runs managed code, so the lock is not held meanwhile
+                    let method = unsafe { inflate(class, name, signature, type_args)? };
+                    Ok(*instantiations.lock().unwrap().entry(key).or_insert(method))
+                }
+
+                unsafe fn error(message: String) -> &'static Il2CppException {
+                    let message = CString::new(message).unwrap();
+                    &*(raw::il2cpp_exception_from_name_msg(
+                        raw::il2cpp_get_corlib(),
+                        b"System\0".as_ptr() as _,
+                        b"MissingMethodException\0".as_ptr() as _,
+                        message.as_ptr(),
+                    ) as *const Il2CppException)
+                }
+
+                /// Invokes the reflection method `name` taking `params` on `object`
+                pub(super) unsafe fn invoke_reflection(
+                    object: *mut Il2CppObject,
+                    name: &[u8],
+                    params: &mut [*mut std::ffi::c_void],
+                ) -> Result<*mut Il2CppObject, &'static Il2CppException> {
+                    let method = raw::il2cpp_class_get_method_from_name(
+                        raw::il2cpp_object_get_class(object as _),
+                        name.as_ptr() as _,
+                        params.len() as _,
+                    );
+                    let mut exception = std::ptr::null_mut();
+                    let result = raw::il2cpp_runtime_invoke(method, object as _, params.as_mut_ptr(), &mut exception);
+                    if exception.is_null() {
+                        Ok(result as _)
+                    } else {
+                        Err(&*(exception as *const Il2CppException))
+                    }
+                }
+
+                /// Whether the parameter types of `method` have the names in `signature`
+                unsafe fn has_signature(method: *const MethodInfo, signature: &[&str]) -> bool {
+                    signature.iter().enumerate().all(|(i, expected)| {
+                        let name = raw::il2cpp_type_get_name(raw::il2cpp_method_get_param(method, i as _));
+                        let matches = CStr::from_ptr(name).to_bytes() == expected.as_bytes();
+                        raw::il2cpp_free(name as _);
+                        matches
+                    })
+                }
+
+                /// Finds the generic method definition to inflate. Overloads are told apart by their
+                /// generic parameter count and parameter types. If no overload has exactly the
+                /// expected parameter type names, the only one with the right counts is used.
+                unsafe fn find_definition(
+                    class: &'static Il2CppClass,
+                    name: &str,
+                    signature: &[&str],
+                    generic_param_count: usize,
+                ) -> Result<*const MethodInfo, &'static Il2CppException> {
+                    let mut candidates = Vec::new();
+                    let mut iter = std::ptr::null_mut();
+                    loop {
+                        let method = raw::il2cpp_class_get_methods(class as *const _ as *mut _, &mut iter);
+                        if method.is_null() {
+                            break;
+                        }
+                        if !raw::il2cpp_method_is_generic(method)
+                            || CStr::from_ptr(raw::il2cpp_method_get_name(method)).to_bytes() != name.as_bytes()
+                            || raw::il2cpp_method_get_param_count(method) as usize != signature.len()
+                        {
+                            continue;
+                        }
+                        let object = raw::il2cpp_method_get_object(method, class);
+                        let generic_args = invoke_reflection(object as _, b"GetGenericArguments\0", &mut [])?;
+                        if raw::il2cpp_array_length(generic_args as _) as usize == generic_param_count {
+                            candidates.push(method);
+                        }
+                    }
+
+                    let exact: Vec<_> = candidates.iter().copied().filter(|m| has_signature(*m, signature)).collect();
+                    match (exact.as_slice(), candidates.as_slice()) {
+                        ([method], _) | ([], [method]) => Ok(*method),
+                        ([], []) => Err(error(format!(
+                            "generic method {}<{}>({}) not found",
+                            name,
+                            generic_param_count,
+                            signature.join(", "),
+                        ))),
+                        _ => Err(error(format!(
+                            "generic method {}({}) is ambiguous",
+                            name,
+                            signature.join(", "),
+                        ))),
+                    }
+                }
+
+                /// `System.Type[]` holding the types of `classes`, which reflection takes type
+                /// arguments as
+                pub(super) unsafe fn type_objects(classes: &[&'static Il2CppClass]) -> *mut std::ffi::c_void {
+                    let type_class = raw::il2cpp_class_from_name(
+                        raw::il2cpp_get_corlib(),
+                        b"System\0".as_ptr() as _,
+                        b"Type\0".as_ptr() as _,
+                    );
+                    let array = raw::il2cpp_array_new(type_class, classes.len() as _);
+                    let elements = (array as *mut u8).add(raw::il2cpp_array_object_header_size() as usize)
+                        as *mut *mut Il2CppObject;
+                    for (i, class) in classes.iter().enumerate() {
+                        *elements.add(i) = raw::il2cpp_type_get_object(raw::il2cpp_class_get_type(*class)) as _;
+                    }
+                    array as _
+                }
+
+                /// il2cpp does not export method inflation, so it is done through reflection by
+                /// calling `MakeGenericMethod` on the method definition
+                unsafe fn inflate(
+                    class: &'static Il2CppClass,
+                    name: &str,
+                    signature: &[&str],
+                    type_args: &[&'static Il2CppClass],
+                ) -> Result<&'static MethodInfo, &'static Il2CppException> {
+                    let definition = find_definition(class, name, signature, type_args.len())?;
+                    let definition_object = raw::il2cpp_method_get_object(definition, class);
+                    let inflated_object = invoke_reflection(
+                        definition_object as _,
+                        b"MakeGenericMethod\0",
+                        &mut [type_objects(type_args)],
+                    )?;
+                    Ok(&*raw::il2cpp_method_get_from_reflection(inflated_object as _))
+                }
+            }
 
             #code
-        }
+        };
+        Ok(Generated {
+            code,
+            skipped: cx.skipped.take(),
+            renames: cx.renames.take(),
+        })
     }
 }
 
 impl<'a> Module<'a> {
-    fn write_tokens(&self, types: &DllData) -> TokenStream {
-        let children_names = self.children.keys().map(|s| create_ident(s));
-        let children = self
-            .children
-            .values()
-            .map(|module| module.write_tokens(types));
-        let types = self.types.iter().cloned().map(|td| td.write_tokens(types));
-        quote! {
+    /// Allocates the identifiers of the items of the module and its children. `path` holds the
+    /// names the modules leading to this one were allocated for. Items are allocated in an order
+    /// that only depends on their names, so clashes are resolved the same way in every run.
+    fn allocate_names(
+        &self,
+        cx: &Context<'_>,
+        path: &mut Vec<String>,
+        reserved: &[String],
+        names: &mut Names,
+    ) {
+        let description = if path.is_empty() {
+            "the root module".to_owned()
+        } else {
+            format!("module {}", path.join("::"))
+        };
+        let mut scope = Scope::new(&cx.renames, description);
+        scope.reserve_all(reserved);
+        if path.is_empty() {
+            scope.reserve_all(HELPER_MODULES);
+        }
+
+        for name in self.children.keys() {
+            path.push(name.clone());
+            names.modules.insert(path.clone(), scope.allocate(name));
+            path.pop();
+        }
+
+        let mut module_types = self.types.clone();
+        module_types.sort_by_cached_key(|(_, td)| (td.base_name(cx), td.type_def_index));
+        for (id, td) in module_types {
+            let name = scope.allocate(&td.base_name(cx));
+
+            // The type's own name can't be shadowed, since its impls refer to it unqualified
+            let mut generic_scope = Scope::new(
+                &cx.renames,
+                format!("the generic parameters of {}", td.cs_full_name(cx.types)),
+            );
+            generic_scope.reserve_all(reserved);
+            generic_scope.reserve(name.clone());
+            let generics = td
+                .this
+                .generics
+                .iter()
+                .map(|generic| generic_scope.allocate(&generic.name))
+                .collect();
+
+            names.types.insert(id, name);
+            names.generics.insert(id, generics);
+        }
+
+        let mut nested_aliases = self.nested_aliases.clone();
+        nested_aliases
+            .sort_by_cached_key(|(_, td)| (td.flattened_base_name(cx), td.type_def_index));
+        for (id, td) in nested_aliases {
+            let name = scope.allocate(&td.flattened_base_name(cx));
+            names.aliases.insert(id, name);
+        }
+
+        for (name, module) in &self.children {
+            path.push(name.clone());
+            module.allocate_names(cx, path, reserved, names);
+            path.pop();
+        }
+    }
+
+    fn write_tokens(
+        &self,
+        cx: &Context<'_>,
+        path: &mut Vec<String>,
+    ) -> Result<TokenStream, GenerateError> {
+        let mut children_names = Vec::new();
+        let mut children = Vec::new();
+        for (name, module) in &self.children {
+            path.push(name.clone());
+            children_names.push(create_ident(&cx.names.modules[path.as_slice()]));
+            children.push(module.write_tokens(cx, path)?);
+            path.pop();
+        }
+        // Types are sorted by name to keep the output identical between runs and across dumps
+        let mut nested_aliases = self.nested_aliases.clone();
+        nested_aliases.sort_by_key(|(id, _)| cx.names.alias(*id).ok());
+        let mut alias_tokens = Vec::new();
+        for (id, td) in nested_aliases {
+            match td.write_nested_alias(cx, id) {
+                Ok(tokens) => alias_tokens.push(tokens),
+                Err(error) => cx.skip(GenerateError::for_type(td.cs_full_name(cx.types), error))?,
+            }
+        }
+        let mut module_types = self.types.clone();
+        module_types.sort_by_key(|(id, _)| cx.names.type_name(*id).ok());
+        let mut type_tokens = Vec::new();
+        for (id, td) in module_types {
+            let tokens = match td.write_tokens(cx, id) {
+                Ok(tokens) => tokens,
+                Err(error) => {
+                    cx.skip(error)?;
+                    // Without a name, not even a stub can be written
+                    match td.write_stub(cx, id) {
+                        Ok(stub) => stub,
+                        Err(error) => {
+                            cx.skip(GenerateError::for_type(td.cs_full_name(cx.types), error))?;
+                            continue;
+                        }
+                    }
+                }
+            };
+            type_tokens.push(tokens);
+        }
+        Ok(quote! {
             #(
                 pub mod #children_names {
                     #children
@@ -472,8 +2080,10 @@ impl<'a> Module<'a> {
             )*
 
             #(
-                #types
+                #type_tokens
             )*
-        }
+
+            #( #alias_tokens )*
+        })
     }
 }
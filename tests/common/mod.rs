@@ -0,0 +1,216 @@
+//! Builders for small dumps, for tests which need a few types with specific members
+#![allow(dead_code)]
+
+use il2cpp_codegen::data::*;
+use il2cpp_codegen::DllData;
+
+/// A dump with the corlib types most members are made of
+pub fn dump() -> DllData {
+    let mut dump = DllData { types: Vec::new() };
+    add_type(&mut dump, "System.Object", TypeEnum::Class);
+    add_type(&mut dump, "System.ValueType", TypeEnum::Class);
+    add_type(&mut dump, "System.Void", TypeEnum::Struct);
+    add_type(&mut dump, "System.Boolean", TypeEnum::Struct);
+    add_type(&mut dump, "System.Int32", TypeEnum::Struct);
+    add_type(&mut dump, "System.Single", TypeEnum::Struct);
+    add_type(&mut dump, "System.String", TypeEnum::Class);
+    // Dumps report the sizes of value types with the object header
+    for (name, size) in [("Void", 17), ("Boolean", 17), ("Int32", 20), ("Single", 20)] {
+        find_mut(&mut dump, &format!("System.{}", name)).size = size;
+    }
+    dump
+}
+
+/// Path of the JSON dump `name` of `tests/fixtures`
+pub fn fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+/// Reads the JSON dump `name` of `tests/fixtures`
+pub fn fixture(name: &str) -> DllData {
+    DllData::from_json_file(fixture_path(name)).unwrap()
+}
+
+/// `Game.Player` holds an `Inventory` and takes an `Enemy` in a method, and types in other
+/// namespaces are there to be filtered out
+pub fn game_dump() -> DllData {
+    let mut dump = dump();
+    add_type(&mut dump, "Game.Inventory", TypeEnum::Class);
+    add_type(&mut dump, "Game.Enemy", TypeEnum::Class);
+    let player = add_type(&mut dump, "Game.Player", TypeEnum::Class);
+    dump.types[player].instance_fields = vec![field(&dump, "inventory", "Game.Inventory", 0x10)];
+    dump.types[player].methods = vec![
+        method(&dump, "Update", "System.Void", &[]),
+        method(&dump, "Jump", "System.Void", &[]),
+        method(
+            &dump,
+            "GetHealth",
+            "System.Single",
+            &[("System.Int32", ParameterModifier::None)],
+        ),
+        method(
+            &dump,
+            "Attack",
+            "System.Void",
+            &[("Game.Enemy", ParameterModifier::None)],
+        ),
+    ];
+    add_type(&mut dump, "Game.Debug.Cheats", TypeEnum::Class);
+    add_type(&mut dump, "Menu.Title", TypeEnum::Class);
+    dump
+}
+
+/// `Game.Player` with a few fields and methods
+pub fn player_dump() -> DllData {
+    let mut dump = dump();
+    let player = add_type(&mut dump, "Game.Player", TypeEnum::Class);
+    dump.types[player].instance_fields = vec![
+        field(&dump, "health", "System.Single", 0x10),
+        field(&dump, "score", "System.Int32", 0x14),
+    ];
+    dump.types[player].methods = vec![
+        method(&dump, "Update", "System.Void", &[]),
+        method(
+            &dump,
+            "Hit",
+            "System.Boolean",
+            &[("System.Int32", ParameterModifier::None)],
+        ),
+    ];
+    dump
+}
+
+/// Adds an empty type called `full_name`, `Namespace.Name`, and returns its id. Classes derive
+/// from `System.Object` and structs from `System.ValueType` if the dump has them.
+pub fn add_type(dump: &mut DllData, full_name: &str, type_enum: TypeEnum) -> usize {
+    let (namespace, name) = full_name.rsplit_once('.').unwrap_or(("", full_name));
+    let parent = match type_enum {
+        TypeEnum::Class => find_id(dump, "System.Object"),
+        TypeEnum::Struct => find_id(dump, "System.ValueType"),
+        TypeEnum::Enum | TypeEnum::Interface | TypeEnum::Unknown => None,
+    }
+    .map(|id| reference(dump, id));
+    let id = dump.types.len();
+    dump.types.push(TypeData {
+        this: TypeDataThis {
+            namespace: namespace.to_owned(),
+            name: name.to_owned(),
+            qualified_cpp_name: full_name.replace('.', "::"),
+            is_generic_template: false,
+            is_nested: false,
+            element_type: None,
+            generic_parameter_constraints: Vec::new(),
+            generics: Vec::new(),
+            declaring_type: None,
+        },
+        attributes: Vec::new(),
+        implementing_interfaces: Vec::new(),
+        instance_fields: Vec::new(),
+        layout: LayoutKind::Auto,
+        methods: Vec::new(),
+        nested_types: Vec::new(),
+        parent,
+        properties: Vec::new(),
+        specifiers: vec!["public".to_owned()],
+        static_fields: Vec::new(),
+        type_enum,
+        type_def_index: id as i32,
+        size: 16,
+    });
+    id
+}
+
+pub fn find_id(dump: &DllData, full_name: &str) -> Option<usize> {
+    dump.types
+        .iter()
+        .position(|ty| ty.cs_full_name(dump) == full_name)
+}
+
+pub fn find_mut<'a>(dump: &'a mut DllData, full_name: &str) -> &'a mut TypeData {
+    let id = find_id(dump, full_name).unwrap_or_else(|| panic!("no type `{}`", full_name));
+    &mut dump.types[id]
+}
+
+/// A reference to the type with the id `id`
+pub fn reference(dump: &DllData, id: usize) -> TypeRef {
+    let ty = &dump.types[id];
+    TypeRef {
+        namespace: ty.this.namespace.clone(),
+        name: ty.this.name.clone(),
+        type_id: id as i32,
+        generics: Vec::new(),
+        is_generic_parameter: false,
+        is_array: false,
+        is_pointer: false,
+        generic_parameter_constraints: Vec::new(),
+        special_constraints: Vec::new(),
+    }
+}
+
+/// A reference to the type called `full_name`
+pub fn type_ref(dump: &DllData, full_name: &str) -> TypeRef {
+    let id = find_id(dump, full_name).unwrap_or_else(|| panic!("no type `{}`", full_name));
+    reference(dump, id)
+}
+
+/// A reference to the generic parameter `name`
+pub fn generic_param(name: &str) -> TypeRef {
+    TypeRef {
+        namespace: String::new(),
+        name: name.to_owned(),
+        type_id: -1,
+        generics: Vec::new(),
+        is_generic_parameter: true,
+        is_array: false,
+        is_pointer: false,
+        generic_parameter_constraints: Vec::new(),
+        special_constraints: Vec::new(),
+    }
+}
+
+pub fn field(dump: &DllData, name: &str, field_type: &str, offset: i32) -> Field {
+    Field {
+        attributes: Vec::new(),
+        name: name.to_owned(),
+        offset,
+        layout_offset: offset,
+        specifiers: vec!["public".to_owned()],
+        field_type: type_ref(dump, field_type),
+        constant: None,
+    }
+}
+
+/// A method taking parameters of the given types and modifiers
+pub fn method(
+    dump: &DllData,
+    name: &str,
+    return_type: &str,
+    parameters: &[(&str, ParameterModifier)],
+) -> Method {
+    Method {
+        attributes: Vec::new(),
+        generic: false,
+        generic_parameters: Vec::new(),
+        hides_base: false,
+        il2cpp_name: name.to_owned(),
+        implemented_from: None,
+        is_special_name: false,
+        is_virtual: false,
+        name: name.to_owned(),
+        offset: 0,
+        parameters: parameters
+            .iter()
+            .enumerate()
+            .map(|(i, (parameter_type, modifier))| Parameter {
+                parameter_type: type_ref(dump, parameter_type),
+                name: format!("arg{}", i),
+                modifier: modifier.clone(),
+            })
+            .collect(),
+        return_type: type_ref(dump, return_type),
+        rva: 0,
+        slot: -1,
+        specifiers: vec!["public".to_owned()],
+        va: 0,
+    }
+}
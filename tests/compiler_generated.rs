@@ -0,0 +1,96 @@
+mod common;
+
+use common::*;
+use il2cpp_codegen::config::CompilerGenerated;
+use il2cpp_codegen::data::TypeEnum;
+use il2cpp_codegen::{DllData, Generator};
+
+/// The player dump with a backing field, a lambda, and the state machine of its `Start` iterator
+fn player_dump() -> DllData {
+    let mut dump = common::player_dump();
+    let player = find_id(&dump, "Game.Player").unwrap();
+    let speed = field(&dump, "<Speed>k__BackingField", "System.Single", 0x18);
+    dump.types[player].instance_fields.push(speed);
+    let start = method(&dump, "Start", "System.Void", &[]);
+    let lambda = method(&dump, "<Start>b__5_0", "System.Void", &[]);
+    dump.types[player].methods.extend([start, lambda]);
+    let iterator = add_type(&mut dump, "<Start>d__5", TypeEnum::Class);
+    dump.types[iterator].this.declaring_type = Some(reference(&dump, player));
+    dump.types[iterator].instance_fields = vec![field(&dump, "<>1__state", "System.Int32", 0x10)];
+    let machine = add_type(&mut dump, "<Load>d__6", TypeEnum::Struct);
+    dump.types[machine].this.declaring_type = Some(reference(&dump, player));
+    let interface = add_type(
+        &mut dump,
+        "System.Runtime.CompilerServices.IAsyncStateMachine",
+        TypeEnum::Interface,
+    );
+    dump.types[machine].implementing_interfaces = vec![reference(&dump, interface)];
+    dump
+}
+
+/// Generated code of `Game.Player` and its nested types, without whitespace
+fn generate(compiler_generated: CompilerGenerated) -> String {
+    let mut generator = Generator::new().include("Game.*");
+    generator.config_mut().filter.compiler_generated = compiler_generated;
+    let code = generator.generate(&player_dump()).unwrap().code;
+    code.split_whitespace().collect()
+}
+
+fn assert_contains(code: &str, expected: &[&str]) {
+    for expected in expected {
+        assert!(code.contains(expected), "{}\n{}", expected, code);
+    }
+}
+
+#[test]
+fn keep() {
+    let code = generate(CompilerGenerated::Keep);
+    assert_contains(
+        &code,
+        &[
+            "pub_Speed_k__BackingField:f32,",
+            "pubfn_Start_b__5_0_0(",
+            "pubstructPlayer__Start_d__5{",
+            "pub__1__state:i32,",
+            "pubstructPlayer__Load_d__6{",
+        ],
+    );
+    assert!(!code.contains("__compiler_generated"), "{}", code);
+}
+
+#[test]
+fn skip() {
+    let code = generate(CompilerGenerated::Skip);
+    // The backing field is still needed for the layout of the type
+    assert_contains(
+        &code,
+        &["pubscore:i32,Speed_BackingField:f32,}", "pubfnStart_0("],
+    );
+    for skipped in [
+        "Lambda",
+        "Iterator",
+        "AsyncStateMachine",
+        "_d__",
+        "__compiler_generated",
+    ] {
+        assert!(!code.contains(skipped), "{}\n{}", skipped, code);
+    }
+}
+
+#[test]
+fn module() {
+    let code = generate(CompilerGenerated::Module);
+    assert_contains(
+        &code,
+        &[
+            "pubmod__compiler_generated{",
+            "pubstructPlayer_Load_AsyncStateMachine{",
+            "pubstructPlayer_Start_Iterator{",
+            "pubstate:i32,",
+            "pubSpeed_BackingField:f32,",
+            "pubfnStart_Lambda5_0_0(",
+        ],
+    );
+    // The classes are still looked up by their mangled names
+    assert_contains(&code, &["(\"<Start>d__5\")", "(\"<Load>d__6\")"]);
+}
@@ -0,0 +1,192 @@
+mod common;
+
+use common::*;
+use il2cpp_codegen::data::{ParameterModifier, TypeEnum};
+use il2cpp_codegen::{Config, DllData, Generator};
+
+fn parse(config: &str) -> anyhow::Result<Config> {
+    let config: Config = toml::from_str(config)?;
+    config.validate()?;
+    Ok(config)
+}
+
+#[test]
+fn paths() {
+    assert!(parse("[output]\nroot = \"crate::il2cpp\"").is_ok());
+    assert!(parse("[output]\nroot = \"crate::il2cpp + 1\"").is_err());
+    assert!(parse("[output]\nroot = \"{ }\"").is_err());
+    assert!(parse("[backend]\nobject = \"Il2CppObject<'a>\"").is_ok());
+    assert!(parse("[backend]\nobject = \"fn main() {}\"").is_err());
+}
+
+#[test]
+fn replacements() {
+    let config = parse(
+        r#"
+        [replacements]
+        "System.IntPtr" = "isize"
+        "System.Void" = "()"
+        "UnityEngine.Vector3" = { path = "[f32; 3]", size = 12 }
+        "UnityEngine.Color" = { path = "crate::Color", size = 16, bounds = true }
+        "#,
+    )
+    .unwrap();
+    let color = &config.replacements["UnityEngine.Color"];
+    assert_eq!(color.path(), "crate::Color");
+    assert_eq!(color.size(), Some(16));
+    assert!(color.bounds());
+    assert!(!config.replacements["UnityEngine.Vector3"].bounds());
+
+    assert!(parse("[replacements]\n\"System.IntPtr\" = \"isize isize\"").is_err());
+    assert!(
+        parse("[replacements]\n\"System.IntPtr\" = { path = \"isize\", bound = true }").is_err()
+    );
+}
+
+#[test]
+fn replacement_bounds_are_opt_in() {
+    let dump = fixture("v1.json");
+    let generate = |replacement: &str| {
+        let config = parse(&format!(
+            "[replacements]\n\"Fixtures.IFoo\" = {}",
+            replacement
+        ))
+        .unwrap();
+        Generator::with_config(config).generate(&dump).unwrap().code
+    };
+
+    let code = generate("\"crate::Foo\"");
+    assert!(!code.contains("for crate::Foo"), "{}", code);
+
+    let code = generate("{ path = \"crate::Foo\", bounds = true }");
+    assert!(
+        code.contains("SubtypeOf<crate::Foo> for crate::Foo"),
+        "{}",
+        code
+    );
+}
+
+#[test]
+fn backend_paths() {
+    let backend = parse("[backend]\nprofile = \"libil2cpp\"").unwrap().backend;
+    assert_eq!(backend.crate_path(), "libil2cpp");
+    assert_eq!(backend.object(), "libil2cpp::Il2CppObject");
+
+    // Items are relative to the runtime crate unless they are paths themselves
+    let backend = parse(
+        r#"
+        [backend]
+        crate = "runtime"
+        object = "Object"
+        string = "crate::strings::Utf16String"
+        "#,
+    )
+    .unwrap()
+    .backend;
+    assert_eq!(backend.object(), "runtime::Object");
+    assert_eq!(backend.string(), "crate::strings::Utf16String");
+    assert_eq!(backend.array(), "runtime::Il2CppArray");
+
+    let mut dump = dump();
+    add_type(&mut dump, "Game.Player", TypeEnum::Class);
+    let config = parse("[backend]\nprofile = \"libil2cpp\"").unwrap();
+    let code = Generator::with_config(config).generate(&dump).unwrap().code;
+    assert!(
+        code.contains("super_: libil2cpp::Il2CppObject,"),
+        "{}",
+        code
+    );
+    assert!(
+        code.contains("libil2cpp::unsafe_impl_reference_type!"),
+        "{}",
+        code
+    );
+    assert!(!code.contains("quest_hook"), "{}", code);
+}
+
+/// A dump with a few types in `Game` whose names and members the tests filter and rename
+#[test]
+fn config_file() {
+    let path = std::env::temp_dir().join(format!("codegen-{}.toml", std::process::id()));
+    std::fs::write(
+        &path,
+        r#"
+        [filter]
+        include = ["Game.*"]
+        exclude = ["Game.Debug.*"]
+
+        [naming]
+        methods = "snake_case"
+
+        [types."Game.Player"]
+        rename = "Hero"
+        methods.Update = { skip = true }
+        methods.Jump = { rename = "leap" }
+
+        [types."Game.Enemy"]
+        skip = true
+        "#,
+    )
+    .unwrap();
+    let generator = Generator::from_config_file(&path);
+    std::fs::remove_file(&path).unwrap();
+    let code = generator.unwrap().generate(&game_dump()).unwrap().code;
+
+    assert!(code.contains("pub struct Hero {"), "{}", code);
+    assert!(!code.contains("pub struct Player"), "{}", code);
+    // The class is still looked up by its il2cpp name
+    assert!(code.contains("for Hero => \"Game\".\"Player\""), "{}", code);
+    assert!(code.contains("pub fn leap_0("), "{}", code);
+    assert!(code.contains("pub fn get_health_0("), "{}", code);
+    assert!(!code.contains("fn update"), "{}", code);
+    for skipped in ["Enemy", "Cheats", "Title"] {
+        assert!(
+            !code.contains(&format!("pub struct {}", skipped)),
+            "{}",
+            code
+        );
+    }
+}
+
+#[test]
+fn skipped_types_are_only_generated_for_layouts() {
+    let mut dump = game_dump();
+    let show = method(
+        &dump,
+        "Show",
+        "System.Void",
+        &[("Menu.Title", ParameterModifier::None)],
+    );
+    find_mut(&mut dump, "Game.Player").methods.push(show);
+    let config = "[filter]\ninclude = [\"Game.Player\"]\n[types.\"Menu.Title\"]\nskip = true";
+    let generate = |dump: &DllData| {
+        Generator::with_config(parse(config).unwrap())
+            .generate(dump)
+            .unwrap()
+            .code
+    };
+
+    // Methods using the skipped type are left out like those using excluded dependencies
+    let code = generate(&dump);
+    assert!(!code.contains("pub struct Title"), "{}", code);
+    assert!(!code.contains("Show"), "{}", code);
+
+    let title = field(&dump, "title", "Menu.Title", 0x10);
+    find_mut(&mut dump, "Game.Player")
+        .instance_fields
+        .push(title);
+    let code = generate(&dump);
+    assert!(code.contains("pub struct Title"), "{}", code);
+}
+
+#[test]
+fn overrides_of_missing_items() {
+    let dump = game_dump();
+    let generate = |config: &str| Generator::with_config(parse(config).unwrap()).generate(&dump);
+    let error = generate("[types.\"Game.Boss\"]\nskip = true").unwrap_err();
+    assert!(error.to_string().contains("Game.Boss"), "{:#}", error);
+    let error = generate("[types.\"Game.Player\"]\nmethods.Fly = { skip = true }").unwrap_err();
+    assert!(error.to_string().contains("Fly"), "{:#}", error);
+
+    assert!(Generator::from_config_file("does-not-exist.toml").is_err());
+}
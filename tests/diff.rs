@@ -0,0 +1,153 @@
+mod common;
+
+use common::*;
+use il2cpp_codegen::data::{ParameterModifier, TypeEnum};
+use il2cpp_codegen::diff::diff;
+use il2cpp_codegen::DllData;
+
+/// The player dump with an overload of `Hit` differing in its modifier, and more members that
+/// the tests change
+fn player_dump() -> DllData {
+    let mut dump = common::player_dump();
+    let alive = field(&dump, "alive", "System.Boolean", 0x18);
+    let hit = method(
+        &dump,
+        "Hit",
+        "System.Boolean",
+        &[("System.Int32", ParameterModifier::Ref)],
+    );
+    let kill = method(&dump, "Kill", "System.Void", &[]);
+    let player = find_mut(&mut dump, "Game.Player");
+    player.instance_fields.push(alive);
+    player.methods.extend([hit, kill]);
+    dump
+}
+
+fn changes(old: &DllData, new: &DllData, name: &str) -> Vec<String> {
+    let report = diff(old, new);
+    report
+        .changed_types
+        .iter()
+        .filter(|type_diff| type_diff.name == name)
+        .flat_map(|type_diff| &type_diff.changes)
+        .map(|change| change.to_string())
+        .collect()
+}
+
+#[test]
+fn identical_dumps() {
+    let report = diff(&player_dump(), &player_dump());
+    assert_eq!(report.to_string(), "");
+}
+
+#[test]
+fn added_removed_and_renamed_types() {
+    let mut old = player_dump();
+    let id = add_type(&mut old, "Game.Removed", TypeEnum::Class);
+    old.types[id].instance_fields = vec![field(&old, "a", "System.Int32", 0x10)];
+    let id = add_type(&mut old, "Game.\u{E000}", TypeEnum::Class);
+    old.types[id].instance_fields = vec![field(&old, "b", "System.Single", 0x10)];
+
+    let mut new = player_dump();
+    add_type(&mut new, "Game.Added", TypeEnum::Struct);
+    let id = add_type(&mut new, "Game.Inventory", TypeEnum::Class);
+    new.types[id].instance_fields = vec![field(&new, "b", "System.Single", 0x10)];
+
+    let report = diff(&old, &new);
+    assert_eq!(report.added_types, ["Game.Added"]);
+    assert_eq!(report.removed_types, ["Game.Removed"]);
+    assert_eq!(report.renamed_types.len(), 1);
+    assert_eq!(report.renamed_types[0].old, "Game.\u{E000}");
+    assert_eq!(report.renamed_types[0].new, "Game.Inventory");
+    assert!(report.changed_types.is_empty());
+}
+
+#[test]
+fn changed_type() {
+    let old = player_dump();
+    let mut new = player_dump();
+    let fields = vec![
+        field(&new, "health", "System.Int32", 0x10),
+        field(&new, "score", "System.Int32", 0x18),
+        field(&new, "level", "System.Int32", 0x1C),
+    ];
+    let player = find_mut(&mut new, "Game.Player");
+    player.type_enum = TypeEnum::Struct;
+    player.size = 0x20;
+    player.instance_fields = fields;
+    player.parent = None;
+
+    assert_eq!(
+        changes(&old, &new, "Game.Player"),
+        [
+            "~ kind: class -> struct",
+            "~ size: 0x10 -> 0x20",
+            "~ parent: System.Object -> none",
+            "- field System.Boolean alive @ 0x18",
+            "~ field health: type System.Single -> System.Int32",
+            "~ field score: offset 0x14 -> 0x18",
+            "+ field System.Int32 level @ 0x1C",
+        ]
+    );
+}
+
+#[test]
+fn overloads_differing_in_modifiers() {
+    let old = player_dump();
+    let mut new = player_dump();
+    let player = find_mut(&mut new, "Game.Player");
+    player.methods[1].rva = 0x1000;
+    player.methods.remove(2);
+
+    assert_eq!(
+        changes(&old, &new, "Game.Player"),
+        [
+            "~ method System.Boolean Hit(System.Int32): RVA 0x0 -> 0x1000",
+            "- method System.Boolean Hit(ref System.Int32)",
+        ]
+    );
+}
+
+#[test]
+fn changed_and_added_methods() {
+    let old = player_dump();
+    let mut new = player_dump();
+    let spawn = method(
+        &new,
+        "Spawn",
+        "System.Void",
+        &[("System.Single", ParameterModifier::None)],
+    );
+    let kill = method(
+        &new,
+        "Kill",
+        "System.Boolean",
+        &[("System.Int32", ParameterModifier::Out)],
+    );
+    let player = find_mut(&mut new, "Game.Player");
+    player.methods[3] = kill;
+    player.methods.push(spawn);
+
+    assert_eq!(
+        changes(&old, &new, "Game.Player"),
+        [
+            "~ method Kill: System.Void Kill() -> System.Boolean Kill(out System.Int32)",
+            "+ method System.Void Spawn(System.Single)",
+        ]
+    );
+}
+
+#[test]
+fn types_sharing_a_name() {
+    let mut old = player_dump();
+    add_type(&mut old, "Game.Player", TypeEnum::Class);
+    let mut new = player_dump();
+    let id = add_type(&mut new, "Game.Player", TypeEnum::Class);
+    new.types[id].size = 0x18;
+    add_type(&mut new, "Game.Player", TypeEnum::Interface);
+
+    let report = diff(&old, &new);
+    assert_eq!(changes(&old, &new, "Game.Player"), ["~ size: 0x10 -> 0x18"]);
+    assert_eq!(report.added_types, ["Game.Player"]);
+    assert!(report.removed_types.is_empty());
+}
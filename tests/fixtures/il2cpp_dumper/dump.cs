@@ -0,0 +1,214 @@
+// Image 0: mscorlib.dll - 0
+// Image 1: Assembly-CSharp.dll - 15
+
+// Namespace: System
+[Serializable]
+public class Object // TypeDefIndex: 0
+{
+	// Methods
+
+	// RVA: 0x1000 Offset: 0x1000 VA: 0x1001000
+	public void .ctor() { }
+
+	// RVA: 0x1010 Offset: 0x1010 VA: 0x1001010 Slot: 0
+	public virtual bool Equals(object obj) { }
+}
+
+// Namespace: System
+public abstract class ValueType // TypeDefIndex: 1
+{}
+
+// Namespace: System
+public abstract class Enum : ValueType // TypeDefIndex: 2
+{}
+
+// Namespace: System
+public struct Void // TypeDefIndex: 3
+{}
+
+// Namespace: System
+public struct Boolean // TypeDefIndex: 4
+{
+	// Fields
+	private bool m_value; // 0x0
+}
+
+// Namespace: System
+public struct Int32 // TypeDefIndex: 5
+{
+	// Fields
+	private int m_value; // 0x0
+	public const int MaxValue = 2147483647;
+}
+
+// Namespace: System
+public struct Single // TypeDefIndex: 6
+{
+	// Fields
+	private float m_value; // 0x0
+}
+
+// Namespace: System
+public sealed class String // TypeDefIndex: 7
+{
+	// Fields
+	private int m_stringLength; // 0x10
+	private char m_firstChar; // 0x14
+}
+
+// Namespace: System
+public struct Char // TypeDefIndex: 8
+{
+	// Fields
+	private char m_value; // 0x0
+}
+
+// Namespace: System
+public struct IntPtr // TypeDefIndex: 9
+{
+	// Fields
+	private void* m_value; // 0x0
+}
+
+// Namespace: System
+public interface IDisposable // TypeDefIndex: 10
+{
+	// Methods
+
+	// RVA: -1 Offset: -1 Slot: 0
+	public abstract void Dispose();
+}
+
+// Namespace: System
+public struct Nullable<T> // TypeDefIndex: 11
+	where T : struct
+{
+	// Fields
+	private readonly bool hasValue; // 0x0
+	internal T value; // 0x8
+}
+
+// Namespace: System.Collections.Generic
+public class List<T> : IDisposable // TypeDefIndex: 12
+{
+	// Fields
+	private T[] _items; // 0x10
+	private int _size; // 0x18
+	private static readonly T[] s_emptyArray; // 0x0
+
+	// Properties
+	public int Count { get; }
+	public T this[int index] { get; set; }
+
+	// Methods
+
+	// RVA: 0x2000 Offset: 0x2000 VA: 0x1002000
+	public void Add(T item) { }
+
+	// RVA: 0x2010 Offset: 0x2010 VA: 0x1002010
+	public int get_Count() { }
+
+	// RVA: 0x2020 Offset: 0x2020 VA: 0x1002020
+	public T get_Item(int index) { }
+
+	// RVA: 0x2030 Offset: 0x2030 VA: 0x1002030
+	public void set_Item(int index, T value) { }
+
+	// RVA: 0x2040 Offset: 0x2040 VA: 0x1002040 Slot: 4
+	public void Dispose() { }
+	/* GenericInstMethod :
+	|
+	|-RVA: 0x2050 Offset: 0x2050 VA: 0x1002050
+	|-List<int>.Add
+	*/
+}
+
+// Namespace: 
+public struct List.Enumerator<T> : IDisposable // TypeDefIndex: 13
+{
+	// Fields
+	private List<T> _list; // 0x0
+	private int _index; // 0x8
+	private T _current; // 0x10
+
+	// Methods
+
+	// RVA: 0x2100 Offset: 0x2100 VA: 0x1002100 Slot: 4
+	public void Dispose() { }
+}
+
+// Namespace: UnityEngine
+public class Object // TypeDefIndex: 14
+{
+	// Fields
+	private IntPtr m_CachedPtr; // 0x10
+}
+
+// Namespace: 
+public enum State // TypeDefIndex: 15
+{
+	// Fields
+	public int value__; // 0x0
+	public const State Idle = 0;
+	public const State Running = 1;
+	public const State Broken = -1;
+}
+
+// Namespace: 
+public interface IDamageable // TypeDefIndex: 16
+{
+	// Methods
+
+	// RVA: -1 Offset: -1 Slot: 0
+	public abstract void Damage(float amount);
+}
+
+// Namespace: 
+public class Player : Object, IDamageable // TypeDefIndex: 17
+{
+	// Fields
+	public string name; // 0x18
+	private float health; // 0x20
+	private State state; // 0x24
+	private List<Player> friends; // 0x28
+	private int[][] grid; // 0x30
+	private float[,] heights; // 0x38
+	private Nullable<int> target; // 0x40
+	private int? score; // 0x48
+	[CompilerGenerated]
+	private static Player <Instance>k__BackingField; // 0x0
+
+	// Properties
+	public static Player Instance { get; set; }
+
+	// Methods
+
+	[CompilerGenerated]
+	// RVA: 0x3000 Offset: 0x3000 VA: 0x1003000
+	public static Player get_Instance() { }
+
+	// RVA: 0x3010 Offset: 0x3010 VA: 0x1003010 Slot: 4
+	public void Damage(float amount) { }
+
+	// RVA: 0x3020 Offset: 0x3020 VA: 0x1003020
+	public bool TryGet(int index, out Player player, ref int count, params object[] args) { }
+
+	// RVA: -1 Offset: -1
+	public T Find<T>(string name, bool includeInactive = False) where T : Object { }
+
+	// RVA: 0x3030 Offset: 0x3030 VA: 0x1003030
+	public void .ctor() { }
+}
+
+// Namespace: 
+[CompilerGenerated]
+private sealed class Player.<>c__DisplayClass12_0 // TypeDefIndex: 18
+{
+	// Fields
+	public Player <>4__this; // 0x10
+
+	// Methods
+
+	// RVA: 0x3100 Offset: 0x3100 VA: 0x1003100
+	internal void <Start>b__0() { }
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use common::*;
+use il2cpp_codegen::Generator;
+
+#[test]
+fn formatted_code_parses() {
+    let generator = Generator::new().include("Game.*");
+    let dump = player_dump();
+    let code = generator.generate(&dump).unwrap().code;
+    let tokens = generator.generate_tokens(&dump).unwrap().code;
+
+    let formatted = syn::parse_file(&code).unwrap();
+    let generated: syn::File = syn::parse2(tokens).unwrap();
+    assert_eq!(formatted.attrs.len(), generated.attrs.len());
+    assert_eq!(formatted.items.len(), generated.items.len());
+}
+
+#[test]
+fn items_are_separated_by_blank_lines() {
+    let code = Generator::new()
+        .include("Game.*")
+        .generate(&player_dump())
+        .unwrap()
+        .code;
+    // The code has no inner attributes, so it can be `include!`d
+    assert!(code.starts_with("/// Traits"), "{}", code);
+    assert!(code.contains("\n\npub mod Game {\n"), "{}", code);
+    assert!(
+        code.contains(
+            "    }\n\n    impl Player {\n        /// Offset: 0\n        pub fn Update_0("
+        ),
+        "{}",
+        code
+    );
+    // Attributes stay on the line above their item, while methods are separated like items
+    assert!(
+        code.contains("#[repr(C)]\n    pub struct Player {"),
+        "{}",
+        code
+    );
+    assert!(
+        code.contains("            unimplemented!()\n        }\n\n        /// Offset: 0\n"),
+        "{}",
+        code
+    );
+    assert!(!code.contains("\n\n\n"), "{}", code);
+    assert!(
+        code.lines()
+            .all(|line| line.len() <= 100 || line.trim_start().starts_with("///")),
+        "{}",
+        code
+    );
+}
@@ -0,0 +1,190 @@
+mod common;
+
+use common::*;
+use il2cpp_codegen::config::{Replacement, Toolchain};
+use il2cpp_codegen::data::{ParameterModifier, TypeEnum};
+use il2cpp_codegen::Generator;
+
+fn has_struct(code: &str, name: &str) -> bool {
+    code.lines()
+        .any(|line| line.trim().starts_with(&format!("pub struct {} ", name)))
+}
+
+#[test]
+fn filters() {
+    let dump = game_dump();
+    let generate = |generator: Generator| generator.generate(&dump).unwrap().code;
+
+    let code = generate(Generator::new());
+    assert!(has_struct(&code, "Title"), "{}", code);
+
+    // Types used by signatures are generated with the included types, unless dependencies are
+    // turned off. Types of instance fields are needed for the layout, so they always are.
+    let code = generate(Generator::new().include("Game.Player"));
+    assert!(has_struct(&code, "Enemy"), "{}", code);
+    assert!(code.contains("pub fn Attack_0("), "{}", code);
+    assert!(!has_struct(&code, "Title"), "{}", code);
+    let code = generate(Generator::new().include("Game.Player").dependencies(false));
+    assert!(has_struct(&code, "Inventory"), "{}", code);
+    assert!(!has_struct(&code, "Enemy"), "{}", code);
+    // Methods using types which are not generated are left out
+    assert!(!code.contains("Attack"), "{}", code);
+
+    let code = generate(
+        Generator::new()
+            .include("Game.*")
+            .exclude("Game.Inventory")
+            .exclude("Game.Enemy")
+            .dependencies(false),
+    );
+    assert!(has_struct(&code, "Inventory"), "{}", code);
+    assert!(!has_struct(&code, "Enemy"), "{}", code);
+}
+
+#[test]
+fn root() {
+    let code = Generator::new()
+        .include("Game.Player")
+        .root("crate::il2cpp")
+        .generate(&game_dump())
+        .unwrap()
+        .code;
+    assert!(
+        code.contains("pub inventory: *mut crate::il2cpp::Game::Inventory,"),
+        "{}",
+        code
+    );
+    assert!(!code.contains("crate::Game::"), "{}", code);
+}
+
+#[test]
+fn one_generator_for_several_dumps() {
+    let generator = Generator::new().include("Game.*");
+    let first = generator.generate(&game_dump()).unwrap().code;
+
+    let mut dump = game_dump();
+    find_mut(&mut dump, "Game.Player").instance_fields.clear();
+    find_mut(&mut dump, "Game.Player").methods.clear();
+    let second = generator.generate(&dump).unwrap().code;
+    assert_ne!(first, second);
+    assert_eq!(first, generator.generate(&game_dump()).unwrap().code);
+}
+
+#[test]
+fn write_to() {
+    let dump = game_dump();
+    let generator = Generator::new().include("Game.*");
+    let path = std::env::temp_dir().join(format!("bindings-{}.rs", std::process::id()));
+    generator.write_to(&dump, &path).unwrap();
+    let written = std::fs::read_to_string(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(written.unwrap(), generator.generate(&dump).unwrap().code);
+
+    let missing = std::env::temp_dir()
+        .join("does-not-exist")
+        .join("bindings.rs");
+    let error = generator.write_to(&dump, &missing).unwrap_err();
+    assert!(error.to_string().contains("Failed to write"), "{:#}", error);
+}
+
+#[test]
+fn generators_share_no_state() {
+    let mut replacing = Generator::new().include("Game.Player");
+    replacing.config_mut().replacements.insert(
+        "Game.Inventory".to_owned(),
+        Replacement::Path("crate::Bag".to_owned()),
+    );
+    let plain = Generator::new().include("Game.Player");
+
+    // Generators run on several threads at once, each with its own replacements
+    let dump = game_dump();
+    let (replaced, not_replaced) = std::thread::scope(|scope| {
+        let replaced = scope.spawn(|| replacing.generate(&dump).unwrap().code);
+        let not_replaced = scope.spawn(|| plain.generate(&dump).unwrap().code);
+        (replaced.join().unwrap(), not_replaced.join().unwrap())
+    });
+    assert!(
+        replaced.contains("pub inventory: *mut crate::Bag,"),
+        "{}",
+        replaced
+    );
+    assert!(
+        not_replaced.contains("pub inventory: *mut crate::Game::Inventory,"),
+        "{}",
+        not_replaced
+    );
+    assert_eq!(not_replaced, plain.generate(&dump).unwrap().code);
+}
+
+#[test]
+fn toolchains() {
+    let dump = game_dump();
+    let nightly = Generator::new().include("Game.*");
+    assert_eq!(
+        nightly.config().output.features(),
+        ["arbitrary_enum_discriminant", "generic_associated_types"]
+    );
+    let stable = Generator::new()
+        .include("Game.*")
+        .toolchain(Toolchain::Stable);
+    assert!(stable.config().output.features().is_empty());
+
+    // Features are enabled by the crate including the code, which has no inner attributes. Both
+    // toolchains use the stable `OnceLock`.
+    for generator in [&nightly, &stable] {
+        let code = generator.generate(&dump).unwrap().code;
+        assert!(!code.contains("#!["), "{}", code);
+        assert!(
+            code.contains("use std::sync::OnceLock as OnceCell;"),
+            "{}",
+            code
+        );
+    }
+
+    // Features can be chosen by hand, like when the generated code is part of a bigger crate
+    let mut generator = Generator::new();
+    generator.config_mut().output.features = Some(vec!["let_chains".to_owned()]);
+    assert_eq!(generator.config().output.features(), ["let_chains"]);
+}
+
+#[test]
+fn skipped_and_renamed_items() {
+    let mut dump = game_dump();
+    add_type(&mut dump, "Game.Result", TypeEnum::Class);
+    let enemy = find_id(&dump, "Game.Enemy").unwrap();
+    let mut hit = method(
+        &dump,
+        "Hit",
+        "System.Void",
+        &[("System.Int32", ParameterModifier::None)],
+    );
+    hit.parameters[0].parameter_type.type_id = 1000;
+    dump.types[enemy].methods = vec![hit];
+
+    // Generating reports what was left out and renamed instead of printing it
+    let generated = Generator::new()
+        .include("Game.*")
+        .keep_going(true)
+        .generate(&dump)
+        .unwrap();
+    let skipped: Vec<String> = generated.skipped.iter().map(|e| e.to_string()).collect();
+    assert_eq!(
+        skipped,
+        ["method `Game.Enemy.Hit`: refers to type id 1000, which is out of range"]
+    );
+    let renames: Vec<String> = generated.renames.iter().map(|r| r.to_string()).collect();
+    assert_eq!(
+        renames,
+        ["renamed `Result` to `Result_1` in module Game to avoid a name collision"]
+    );
+
+    let path = std::env::temp_dir().join(format!("skipped-{}.rs", std::process::id()));
+    let written = Generator::new()
+        .include("Game.*")
+        .keep_going(true)
+        .write_to(&dump, &path)
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(written.skipped.len(), 1);
+    assert_eq!(written.renames, generated.renames);
+}
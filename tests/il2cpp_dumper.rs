@@ -0,0 +1,151 @@
+use il2cpp_codegen::data::TypeData;
+use il2cpp_codegen::{validate, DllData, Generator};
+use std::fs;
+
+fn import() -> DllData {
+    let dir = format!(
+        "{}/tests/fixtures/il2cpp_dumper",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    DllData::from_il2cpp_dumper(dir).unwrap()
+}
+
+fn find<'a>(dump: &'a DllData, full_name: &str) -> &'a TypeData {
+    dump.types
+        .iter()
+        .find(|ty| ty.cs_full_name(dump) == full_name)
+        .unwrap_or_else(|| panic!("`{}` was not imported", full_name))
+}
+
+#[test]
+fn types() {
+    let dump = import();
+    let enumerator = find(&dump, "System.Collections.Generic.List`1/Enumerator");
+    assert_eq!(enumerator.this.generics.len(), 1);
+    assert_eq!(enumerator.type_def_index, 13);
+
+    let nullable = find(&dump, "System.Nullable`1");
+    assert_eq!(nullable.this.generics[0].special_constraints, ["struct"]);
+
+    let state = find(&dump, "GlobalNamespace.State");
+    assert_eq!(state.instance_fields[0].offset, 16);
+    assert_eq!(state.static_fields[2].constant.as_deref(), Some("-1"));
+    assert_eq!(state.size, 20);
+
+    let report = validate::validate(&dump);
+    assert!(report.problems.is_empty(), "{}", report);
+}
+
+#[test]
+fn names_resolve_to_the_closest_namespace() {
+    let dump = import();
+    let player = find(&dump, "GlobalNamespace.Player");
+    let parent = player.parent.as_ref().unwrap();
+    assert_eq!(parent.cs_name(&dump), "UnityEngine.Object");
+    assert_eq!(
+        player.implementing_interfaces[0].cs_name(&dump),
+        "GlobalNamespace.IDamageable"
+    );
+
+    let field_types: Vec<String> = player
+        .instance_fields
+        .iter()
+        .map(|field| field.field_type.cs_name(&dump))
+        .collect();
+    assert_eq!(
+        field_types,
+        [
+            "System.String",
+            "System.Single",
+            "GlobalNamespace.State",
+            "System.Collections.Generic.List`1<GlobalNamespace.Player>",
+            "System.Int32[][]",
+            "System.Single[,]",
+            "System.Nullable`1<System.Int32>",
+            "System.Nullable`1<System.Int32>",
+        ]
+    );
+}
+
+#[test]
+fn methods() {
+    let dump = import();
+    let player = find(&dump, "GlobalNamespace.Player");
+    let keys: Vec<String> = player.methods.iter().map(|m| m.key(&dump)).collect();
+    assert_eq!(
+        keys,
+        [
+            "get_Instance()",
+            "Damage(System.Single)",
+            "TryGet(System.Int32, out GlobalNamespace.Player, ref System.Int32, params System.Object[])",
+            "Find<T>(System.String, System.Boolean)",
+            ".ctor()",
+        ]
+    );
+    assert_eq!(player.methods[1].rva, 0x3010);
+    assert_eq!(player.methods[1].slot, 4);
+    assert!(player.methods[0].is_special_name);
+
+    let constraints = &player.methods[3].generic_parameters[0].generic_parameter_constraints;
+    assert_eq!(constraints[0].cs_name(&dump), "UnityEngine.Object");
+}
+
+#[test]
+fn addresses_from_script_json() {
+    let dir = std::env::temp_dir().join("il2cpp_codegen_script_json");
+    fs::create_dir_all(&dir).unwrap();
+    let dump_cs = "// Namespace: System\n\
+                   public class Object // TypeDefIndex: 0\n\
+                   {\n\
+                   \tpublic void .ctor() { }\n\
+                   \tpublic bool Equals(object obj) { }\n\
+                   }\n";
+    let script = r#"{ "ScriptMethod": [
+        { "Address": 4096, "Name": "System.Object$$.ctor" },
+        { "Address": 4112, "Name": "System.Object$$Equals" }
+    ] }"#;
+    fs::write(dir.join("dump.cs"), dump_cs).unwrap();
+    fs::write(dir.join("script.json"), script).unwrap();
+
+    let dump = DllData::from_il2cpp_dumper(&dir).unwrap();
+    let rvas: Vec<i32> = dump.types[0].methods.iter().map(|m| m.rva).collect();
+    assert_eq!(rvas, [0x1000, 0x1010]);
+}
+
+#[test]
+fn arrays_are_not_emitted_as_classes() {
+    let code = Generator::new().generate(&import()).unwrap().code;
+    for composite in ["Int32[][]", "Single[,]", "Object[]"] {
+        assert!(!code.contains(composite), "`{}` was emitted", composite);
+    }
+    assert!(!code.contains("pub struct Int32_"), "{}", code);
+}
+
+#[test]
+fn generate() {
+    let code = Generator::new().generate(&import()).unwrap().code;
+    assert!(code.contains("SubtypeOf<crate::UnityEngine::Object> for Player"));
+    // Generic methods are looked up by the il2cpp names of their parameter types
+    assert!(
+        code.contains(r#"&["System.String", "System.Boolean"]"#),
+        "{}",
+        code
+    );
+
+    // Nested classes are found through their declaring class, which takes their generics
+    let compact: String = code.split_whitespace().collect();
+    assert!(
+        compact.contains(
+            r#"unsafeimpl<T:quest_hook::libil2cpp::Type>quest_hook::libil2cpp::TypeforList_1_Enumerator<T>{typeHeld<'a>=Self;"#
+        ),
+        "{}",
+        compact
+    );
+    assert!(
+        compact.contains(
+            r#"crate::__nested_classes::find::<crate::System::Collections::Generic::List_1<T>,>("Enumerator").and_then(|definition|crate::__nested_classes::inflate(definition,&[<Tasquest_hook::libil2cpp::Type>::class()]"#
+        ),
+        "{}",
+        compact
+    );
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use common::*;
+use il2cpp_codegen::config::CompilerGenerated;
+use il2cpp_codegen::data::{ParameterModifier, TypeEnum};
+use il2cpp_codegen::error::{ErrorKind, GenerateError, MemberKind};
+use il2cpp_codegen::Generator;
+
+/// Generated code without whitespace, so assertions don't depend on the formatting
+fn compact(code: &str) -> String {
+    code.split_whitespace().collect()
+}
+
+#[test]
+fn keep_going() {
+    let mut dump = dump();
+    add_type(&mut dump, "Game.State", TypeEnum::Enum);
+    let player = add_type(&mut dump, "Game.Player", TypeEnum::Class);
+    let mut hit = method(
+        &dump,
+        "Hit",
+        "System.Void",
+        &[("System.Int32", ParameterModifier::None)],
+    );
+    hit.parameters[0].parameter_type.type_id = 1000;
+    dump.types[player].methods = vec![hit, method(&dump, "Kill", "System.Void", &[])];
+
+    let error = Generator::new()
+        .include("Game.*")
+        .exclude("Game.State")
+        .generate(&dump)
+        .unwrap_err();
+    let error = error.downcast_ref::<GenerateError>().unwrap();
+    assert_eq!(error.type_name, "Game.Player");
+    assert_eq!(error.member, Some((MemberKind::Method, "Hit".to_owned())));
+    assert_eq!(error.kind, ErrorKind::TypeIdOutOfRange(1000));
+    assert!(!error.stubs_type());
+
+    let error = Generator::new()
+        .include("Game.*")
+        .exclude("Game.Player")
+        .generate(&dump)
+        .unwrap_err();
+    let error = error.downcast_ref::<GenerateError>().unwrap();
+    assert_eq!(
+        error.to_string(),
+        "type `Game.State`: the enum has no value field"
+    );
+    assert!(error.stubs_type());
+
+    // Types which can't be generated are stubbed, and members are left out
+    let code = Generator::new()
+        .include("Game.*")
+        .keep_going(true)
+        .generate(&dump)
+        .unwrap()
+        .code;
+    let code = compact(&code);
+    assert!(
+        code.contains("///Stubforatypewhichcouldnotbegenerated#[repr(C)]pubstructState{__stub:"),
+        "{}",
+        code
+    );
+    assert!(code.contains("pubfnKill_0("), "{}", code);
+    assert!(!code.contains("Hit"), "{}", code);
+}
+
+#[test]
+fn out_of_range_declaring_type() {
+    let mut dump = dump();
+    add_type(&mut dump, "Game.Enemy", TypeEnum::Class);
+    let player = add_type(&mut dump, "Game.Player", TypeEnum::Class);
+    let mut declaring_type = type_ref(&dump, "Game.Enemy");
+    declaring_type.type_id = 99;
+    dump.types[player].this.declaring_type = Some(declaring_type);
+
+    for compiler_generated in [CompilerGenerated::Skip, CompilerGenerated::Module] {
+        let mut generator = Generator::new().include("Game.*");
+        generator.config_mut().filter.compiler_generated = compiler_generated;
+        assert!(generator.generate(&dump).is_err());
+
+        let code = generator.keep_going(true).generate(&dump).unwrap().code;
+        assert!(code.contains("pub struct Enemy"), "{}", code);
+    }
+}
+
+#[test]
+fn nested_type_without_declaring_generics() {
+    let mut dump = dump();
+    let list = add_type(&mut dump, "Game.List`1", TypeEnum::Class);
+    dump.types[list].this.generics = vec![generic_param("T")];
+    let node = add_type(&mut dump, "Node", TypeEnum::Class);
+    // The nested type should repeat the generic parameter of `List`1`
+    dump.types[node].this.declaring_type = Some(reference(&dump, list));
+
+    let error = Generator::new()
+        .include("Game.*")
+        .generate(&dump)
+        .unwrap_err();
+    let error = error.downcast_ref::<GenerateError>().unwrap();
+    assert_eq!(error.type_name, "Game.List`1/Node");
+    assert_eq!(
+        error.kind,
+        ErrorKind::MissingDeclaringGenerics {
+            expected: 1,
+            found: 0
+        }
+    );
+
+    let code = Generator::new()
+        .include("Game.*")
+        .keep_going(true)
+        .generate(&dump)
+        .unwrap()
+        .code;
+    let code = compact(&code);
+    assert!(code.contains("pubstructList_1_Node{__stub:"), "{}", code);
+}
+
+#[test]
+fn empty_namespace_segment() {
+    let mut dump = dump();
+    add_type(&mut dump, "A..B.Player", TypeEnum::Class);
+
+    for keep_going in [false, true] {
+        let code = Generator::new()
+            .include("A*")
+            .keep_going(keep_going)
+            .generate(&dump)
+            .unwrap()
+            .code;
+        let code = compact(&code);
+        assert!(
+            code.contains("pubmodA{pubmodNamespace_1{pubmodB{#[repr(C)]pubstructPlayer{"),
+            "{}",
+            code
+        );
+    }
+}
@@ -0,0 +1,156 @@
+mod common;
+
+use common::*;
+use il2cpp_codegen::data::{ParameterModifier, TypeEnum};
+use il2cpp_codegen::matching::{match_dumps, MatchReport};
+use il2cpp_codegen::DllData;
+
+/// Adds a class with fields of the given types at consecutive offsets
+fn add_class(dump: &mut DllData, full_name: &str, field_types: &[&str]) -> usize {
+    let id = add_type(dump, full_name, TypeEnum::Class);
+    let fields = field_types
+        .iter()
+        .enumerate()
+        .map(|(i, ty)| field(dump, &format!("field{}", i), ty, 0x10 + 8 * i as i32))
+        .collect();
+    dump.types[id].instance_fields = fields;
+    dump.types[id].size = 0x10 + 8 * field_types.len() as i32;
+    id
+}
+
+fn type_matches(report: &MatchReport) -> Vec<(&str, &str)> {
+    report
+        .types
+        .iter()
+        .map(|m| (m.old.as_str(), m.new.as_str()))
+        .collect()
+}
+
+#[test]
+fn renamed_types_are_paired_by_shape() {
+    let mut old = dump();
+    add_class(&mut old, "Game.\u{E000}", &["System.Int32"]);
+    add_class(
+        &mut old,
+        "Game.\u{E001}",
+        &["System.Single", "System.Single"],
+    );
+    add_class(&mut old, "Game.Kept", &[]);
+    let mut new = dump();
+    add_class(
+        &mut new,
+        "Game.Velocity",
+        &["System.Single", "System.Single"],
+    );
+    add_class(&mut new, "Game.Health", &["System.Int32"]);
+    add_class(&mut new, "Game.Kept", &[]);
+
+    let report = match_dumps(&old, &new, 0.0);
+    assert_eq!(
+        type_matches(&report),
+        [
+            ("Game.\u{E000}", "Game.Health"),
+            ("Game.\u{E001}", "Game.Velocity"),
+        ]
+    );
+    // The other candidate shares the base class and methods, which costs some confidence
+    assert!(report.types.iter().all(|m| m.confidence > 0.6));
+}
+
+#[test]
+fn ambiguous_candidates_lower_the_confidence() {
+    let mut old = dump();
+    add_class(&mut old, "Game.\u{E000}", &["System.Int32"]);
+    let mut new = dump();
+    add_class(&mut new, "Game.Health", &["System.Int32"]);
+    add_class(&mut new, "Game.Armor", &["System.Int32"]);
+
+    // Both candidates score 1, so the confidence is lowered by half the runner-up's score
+    let report = match_dumps(&old, &new, 0.0);
+    assert_eq!(report.types.len(), 1);
+    assert_eq!(report.types[0].confidence, 0.5);
+
+    let report = match_dumps(&old, &new, 0.6);
+    assert!(report.types.is_empty());
+}
+
+#[test]
+fn matched_types_anchor_the_next_round() {
+    // The holders only differ in the types of their fields, which are renamed too. Only once the
+    // first round matched the field types, the second round can tell the holders apart.
+    let mut old = dump();
+    add_class(&mut old, "Game.\u{E000}", &["System.Int32"]);
+    add_class(
+        &mut old,
+        "Game.\u{E001}",
+        &["System.Single", "System.Single"],
+    );
+    add_class(&mut old, "Game.\u{E002}", &["Game.\u{E000}"]);
+    add_class(&mut old, "Game.\u{E003}", &["Game.\u{E001}"]);
+    let mut new = dump();
+    add_class(
+        &mut new,
+        "Game.Velocity",
+        &["System.Single", "System.Single"],
+    );
+    add_class(&mut new, "Game.Health", &["System.Int32"]);
+    add_class(&mut new, "Game.VelocityHolder", &["Game.Velocity"]);
+    add_class(&mut new, "Game.HealthHolder", &["Game.Health"]);
+
+    let report = match_dumps(&old, &new, 0.0);
+    let matches = type_matches(&report);
+    assert!(matches.contains(&("Game.\u{E002}", "Game.HealthHolder")));
+    assert!(matches.contains(&("Game.\u{E003}", "Game.VelocityHolder")));
+    for m in &report.types {
+        assert!(
+            m.confidence > 0.5,
+            "{} -> {}: {}",
+            m.old,
+            m.new,
+            m.confidence
+        );
+    }
+}
+
+#[test]
+fn renamed_members() {
+    let mut old = dump();
+    let id = add_class(&mut old, "Game.Player", &["System.Int32", "System.Single"]);
+    old.types[id].instance_fields[1].name = "\u{E000}".to_owned();
+    old.types[id].methods = vec![
+        method(&old, "Update", "System.Void", &[]),
+        method(
+            &old,
+            "\u{E001}",
+            "System.Boolean",
+            &[("System.Int32", ParameterModifier::None)],
+        ),
+    ];
+    let mut new = dump();
+    let id = add_class(&mut new, "Game.Player", &["System.Int32", "System.Single"]);
+    new.types[id].instance_fields[1].name = "speed".to_owned();
+    new.types[id].methods = vec![
+        method(&new, "Update", "System.Void", &[]),
+        method(
+            &new,
+            "TryHit",
+            "System.Boolean",
+            &[("System.Int32", ParameterModifier::None)],
+        ),
+    ];
+
+    let report = match_dumps(&old, &new, 0.0);
+    assert!(report.types.is_empty());
+    let fields: Vec<_> = report
+        .fields
+        .iter()
+        .map(|m| (&m.old[..], &m.new[..]))
+        .collect();
+    assert_eq!(fields, [("\u{E000}", "speed")]);
+    let methods: Vec<_> = report
+        .methods
+        .iter()
+        .map(|m| (&m.old[..], &m.new[..]))
+        .collect();
+    assert_eq!(methods, [("\u{E001}", "TryHit")]);
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use common::*;
+use il2cpp_codegen::data::{ParameterModifier, TypeEnum};
+use il2cpp_codegen::{DllData, Generator};
+
+/// Generated code of the types in `Game`, without whitespace
+fn generate(dump: &DllData) -> String {
+    let code = Generator::new()
+        .include("Game.*")
+        .generate(dump)
+        .unwrap()
+        .code;
+    code.split_whitespace().collect()
+}
+
+#[test]
+fn unnamable_items_get_fallback_names() {
+    let mut dump = dump();
+    let obfuscated = add_type(&mut dump, "Game.\u{E000}", TypeEnum::Class);
+    let mut pointer = field(&dump, "\u{E002}", "System.Single", 0x18);
+    pointer.field_type.is_pointer = true;
+    dump.types[obfuscated].instance_fields = vec![
+        field(&dump, "\u{E001}", "System.Int32", 0x10),
+        field(&dump, "__", "System.Int32", 0x14),
+        pointer,
+        field(&dump, "score", "System.Int32", 0x20),
+    ];
+    dump.types[obfuscated].methods = vec![
+        method(&dump, "Update", "System.Void", &[]),
+        method(
+            &dump,
+            "\u{E003}",
+            "System.Boolean",
+            &[("System.Int32", ParameterModifier::None)],
+        ),
+    ];
+    let code = generate(&dump);
+
+    // Types are named after their kind and type definition index, members after their types and
+    // position
+    let name = format!("Class_{}", obfuscated);
+    assert!(code.contains(&format!("pubstruct{}{{", name)), "{}", code);
+    for expected in [
+        "pubfield_Int32_0:i32,",
+        "pubfield_Int32_1:i32,",
+        "pubfield_SinglePtr_2:*mutf32,",
+        "pubscore:i32,",
+        "pubfnUpdate_0(",
+        "pubfnmethod_Boolean_Int32_1(",
+    ] {
+        assert!(code.contains(expected), "{}\n{}", expected, code);
+    }
+    // The class is still looked up by its il2cpp name
+    let lookup = format!(r#"for{}=>"Game"."\u{{e000}}""#, name);
+    assert!(code.contains(&lookup), "{}", code);
+}
+
+#[test]
+fn colliding_names_are_suffixed() {
+    let mut dump = dump();
+    add_type(&mut dump, "Game.Result", TypeEnum::Class);
+    add_type(&mut dump, "Game.Hit Box", TypeEnum::Class);
+    add_type(&mut dump, "Game.Hit_Box", TypeEnum::Class);
+    let player = add_type(&mut dump, "Game.Player", TypeEnum::Class);
+    dump.types[player].this.is_generic_template = true;
+    dump.types[player].this.generics = vec![generic_param("Player"), generic_param("i32")];
+    dump.types[player].instance_fields = vec![
+        field(&dump, "super", "System.Int32", 0x10),
+        field(&dump, "super_", "System.Int32", 0x14),
+        field(&dump, "type", "System.Int32", 0x18),
+    ];
+    add_type(&mut dump, "std.Game", TypeEnum::Class);
+    add_type(&mut dump, "__bounds.Game", TypeEnum::Class);
+    let code = Generator::new()
+        .include("Game.*")
+        .include("std.*")
+        .include("__bounds.*")
+        .generate(&dump)
+        .unwrap()
+        .code;
+    println!("{}", code);
+}
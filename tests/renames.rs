@@ -0,0 +1,75 @@
+mod common;
+
+use common::*;
+use il2cpp_codegen::data::TypeEnum;
+use il2cpp_codegen::{DllData, Generator};
+use std::path::PathBuf;
+
+/// Writes `renames` to a file named after `name` in the temporary directory
+fn rename_file(name: &str, renames: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("renames-{}-{}.json", name, std::process::id()));
+    std::fs::write(&path, renames).unwrap();
+    path
+}
+
+fn generate(dump: &DllData, name: &str, renames: &str) -> anyhow::Result<String> {
+    let path = rename_file(name, renames);
+    let generator = Generator::new().include("Game.*").renames(&path);
+    std::fs::remove_file(&path).unwrap();
+    Ok(generator?.generate(dump)?.code)
+}
+
+/// A dump with the obfuscated types `Game.\u{E000}` and `Game.\u{E001}`
+fn obfuscated_dump() -> DllData {
+    let mut dump = dump();
+    let player = add_type(&mut dump, "Game.\u{E000}", TypeEnum::Class);
+    dump.types[player].instance_fields = vec![field(&dump, "\u{E002}", "System.Int32", 0x10)];
+    dump.types[player].methods = vec![method(&dump, "\u{E003}", "System.Void", &[])];
+    add_type(&mut dump, "Game.\u{E001}", TypeEnum::Class);
+    dump
+}
+
+#[test]
+fn renamed_types_and_members() {
+    let dump = obfuscated_dump();
+    let player = find_id(&dump, "Game.\u{E000}").unwrap();
+    let renames = format!(
+        r#"{{
+            "types": {{ "{player}": "Player", "Game.\uE001": "Enemy" }},
+            "methods": {{ "{player}": {{ "\uE003": "Update" }} }},
+            "fields": {{ "Game.\uE000": {{ "\uE002": "score" }} }}
+        }}"#,
+        player = player
+    );
+    let code = generate(&dump, "types", &renames).unwrap();
+    let compact: String = code.split_whitespace().collect();
+
+    assert!(compact.contains("pubstructPlayer{"), "{}", code);
+    assert!(compact.contains("pubstructEnemy{"), "{}", code);
+    assert!(compact.contains("pubscore:i32,"), "{}", code);
+    assert!(compact.contains("pubfnUpdate_0("), "{}", code);
+    // Classes are still looked up by their il2cpp names
+    assert!(
+        compact.contains(r#"forPlayer=>"Game"."\u{e000}""#),
+        "{}",
+        code
+    );
+}
+
+#[test]
+fn invalid_rename_maps() {
+    let dump = obfuscated_dump();
+    let error = generate(
+        &dump,
+        "ident",
+        r#"{ "types": { "Game.\uE000": "Not a name" } }"#,
+    )
+    .unwrap_err();
+    assert!(
+        format!("{:#}", error).contains("not an identifier"),
+        "{:#}",
+        error
+    );
+    assert!(generate(&dump, "unknown", r#"{ "namespaces": {} }"#).is_err());
+    assert!(Generator::new().renames("does-not-exist.json").is_err());
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use common::*;
+use il2cpp_codegen::data::TypeRef;
+use il2cpp_codegen::schema::{self, SchemaVersion};
+use il2cpp_codegen::{DllData, Generator};
+
+/// The parameter `T` of `Fixtures.Box<T>`, which all fixtures constrain to `Fixtures.IFoo`
+fn box_parameter(dump: &DllData) -> &TypeRef {
+    let ty = dump
+        .types
+        .iter()
+        .find(|ty| ty.this.name == "Box`1")
+        .unwrap();
+    &ty.this.generics[0]
+}
+
+fn check(name: &str, expected: SchemaVersion) {
+    let (dump, version) = DllData::read_json(fixture_path(name)).unwrap();
+    assert_eq!(version, expected);
+
+    let constraints = &box_parameter(&dump).generic_parameter_constraints;
+    assert_eq!(constraints.len(), 1);
+    assert_eq!(constraints[0].name, "IFoo");
+
+    let code = Generator::new().generate(&dump).unwrap().code;
+    assert!(code.contains("SubtypeOf"), "{}", code);
+}
+
+#[test]
+fn v1() {
+    check("v1.json", SchemaVersion::V1);
+}
+
+#[test]
+fn future_version_reads_as_latest() {
+    check("future.json", SchemaVersion::LATEST);
+}
+
+#[test]
+fn constraints_of_several_parameters() {
+    let mut dump = fixture("v1.json");
+    let ty = dump
+        .types
+        .iter_mut()
+        .find(|ty| ty.this.name == "Box`1")
+        .unwrap();
+    let mut second = ty.this.generics[0].clone();
+    second.name = "U".to_owned();
+    second.generic_parameter_constraints.clear();
+    ty.this.generics[0].generic_parameter_constraints.clear();
+    ty.this.generics.push(second);
+
+    // The constraints of the type can't be told apart, so neither parameter gets them
+    schema::upgrade(&mut dump, SchemaVersion::V1);
+    let constraints = |dump: &DllData, i: usize| {
+        dump.types[1].this.generics[i]
+            .generic_parameter_constraints
+            .len()
+    };
+    assert_eq!((constraints(&dump, 0), constraints(&dump, 1)), (0, 0));
+
+    // Constraints given for a parameter are kept
+    let foo = dump.types[1].this.generic_parameter_constraints.clone();
+    dump.types[1].this.generics[1].generic_parameter_constraints = foo;
+    schema::upgrade(&mut dump, SchemaVersion::V1);
+    assert_eq!((constraints(&dump, 0), constraints(&dump, 1)), (0, 1));
+}
@@ -0,0 +1,152 @@
+mod common;
+
+use common::*;
+use il2cpp_codegen::data::TypeEnum;
+use il2cpp_codegen::validate::{validate, Category};
+use il2cpp_codegen::DllData;
+
+fn categories(dump: &DllData) -> Vec<Category> {
+    validate(dump)
+        .problems
+        .iter()
+        .map(|problem| problem.category)
+        .collect()
+}
+
+#[test]
+fn valid_dump() {
+    let mut dump = dump();
+    let id = add_type(&mut dump, "Game.Player", TypeEnum::Class);
+    dump.types[id].size = 0x18;
+    dump.types[id].instance_fields = vec![field(&dump, "score", "System.Int32", 0x10)];
+    let report = validate(&dump);
+    assert!(report.problems.is_empty(), "{}", report);
+}
+
+#[test]
+fn type_id_out_of_range() {
+    let mut dump = dump();
+    let id = add_type(&mut dump, "Game.Player", TypeEnum::Class);
+    let mut score = field(&dump, "score", "System.Int32", 0x10);
+    score.field_type.type_id = 1000;
+    dump.types[id].size = 0x18;
+    dump.types[id].instance_fields = vec![score];
+    assert_eq!(categories(&dump), [Category::TypeIdOutOfRange]);
+    assert!(Category::TypeIdOutOfRange.is_error());
+}
+
+#[test]
+fn declaring_type_cycle() {
+    let mut dump = dump();
+    let outer = add_type(&mut dump, "Game.Outer", TypeEnum::Class);
+    let inner = add_type(&mut dump, "Game.Inner", TypeEnum::Class);
+    dump.types[outer].this.declaring_type = Some(reference(&dump, inner));
+    dump.types[inner].this.declaring_type = Some(reference(&dump, outer));
+    assert_eq!(
+        categories(&dump),
+        [Category::DeclaringTypeCycle, Category::DeclaringTypeCycle]
+    );
+}
+
+#[test]
+fn missing_enum_value() {
+    let mut dump = dump();
+    add_type(&mut dump, "Game.State", TypeEnum::Enum);
+    assert_eq!(categories(&dump), [Category::MissingEnumValue]);
+}
+
+#[test]
+fn invalid_inheritance() {
+    let mut dump = dump();
+    let interface = add_type(&mut dump, "Game.IDamageable", TypeEnum::Interface);
+    let player = add_type(&mut dump, "Game.Player", TypeEnum::Class);
+    let enemy = add_type(&mut dump, "Game.Enemy", TypeEnum::Class);
+    dump.types[player].parent = Some(reference(&dump, interface));
+    dump.types[enemy].implementing_interfaces = vec![type_ref(&dump, "Game.Player")];
+    assert_eq!(
+        categories(&dump),
+        [Category::InvalidInheritance, Category::InvalidInheritance]
+    );
+}
+
+#[test]
+fn generic_arity() {
+    let mut dump = dump();
+    let list = add_type(&mut dump, "Game.List`1", TypeEnum::Class);
+    dump.types[list].this.is_generic_template = true;
+    dump.types[list].this.generics = vec![generic_param("T")];
+    let player = add_type(&mut dump, "Game.Player", TypeEnum::Class);
+    let mut items = reference(&dump, list);
+    items.generics = vec![
+        type_ref(&dump, "System.Int32"),
+        type_ref(&dump, "System.Single"),
+    ];
+    dump.types[player].parent = Some(items);
+    assert_eq!(categories(&dump), [Category::GenericArity]);
+}
+
+#[test]
+fn duplicate_name() {
+    let mut dump = dump();
+    add_type(&mut dump, "Game.Player", TypeEnum::Class);
+    add_type(&mut dump, "Game.Player", TypeEnum::Class);
+
+    // Nested types of other declaring types, other generic arities and generic instances are
+    // no duplicates
+    let outer = add_type(&mut dump, "Game.Outer", TypeEnum::Class);
+    let nested = add_type(&mut dump, "Game.Player", TypeEnum::Class);
+    dump.types[nested].this.declaring_type = Some(reference(&dump, outer));
+    let generic = add_type(&mut dump, "Game.Player", TypeEnum::Class);
+    dump.types[generic].this.is_generic_template = true;
+    dump.types[generic].this.generics = vec![generic_param("T")];
+    let instance = add_type(&mut dump, "Game.Player", TypeEnum::Class);
+    dump.types[instance].this.generics = vec![type_ref(&dump, "System.Int32")];
+
+    let report = validate(&dump);
+    let problems: Vec<_> = report
+        .problems
+        .iter()
+        .map(|problem| (problem.category, problem.message.as_str()))
+        .collect();
+    let first = find_id(&dump, "Game.Player").unwrap();
+    assert_eq!(
+        problems,
+        [(
+            Category::DuplicateName,
+            format!("the name is shared by the types {}, {}", first, first + 1).as_str()
+        )]
+    );
+}
+
+#[test]
+fn implausible_size() {
+    let mut dump = dump();
+    let negative = add_type(&mut dump, "Game.Negative", TypeEnum::Class);
+    dump.types[negative].size = -1;
+
+    let player = add_type(&mut dump, "Game.Player", TypeEnum::Class);
+    dump.types[player].size = 0x18;
+    dump.types[player].instance_fields = vec![field(&dump, "score", "System.Int32", 0x18)];
+
+    // Offsets of value type fields include the object header, which their size may not
+    let position = add_type(&mut dump, "Game.Position", TypeEnum::Struct);
+    dump.types[position].size = 8;
+    dump.types[position].instance_fields = vec![
+        field(&dump, "x", "System.Int32", 0x10),
+        field(&dump, "y", "System.Int32", 0x14),
+    ];
+
+    let report = validate(&dump);
+    let types: Vec<_> = report
+        .problems
+        .iter()
+        .map(|problem| (problem.category, problem.type_name.as_str()))
+        .collect();
+    assert_eq!(
+        types,
+        [
+            (Category::ImplausibleSize, "Game.Negative"),
+            (Category::ImplausibleSize, "Game.Player"),
+        ]
+    );
+}
@@ -0,0 +1,162 @@
+mod common;
+
+use common::*;
+use il2cpp_codegen::data::{ParameterModifier, TypeEnum};
+use il2cpp_codegen::{DllData, Generator};
+
+/// A dump with `Game.Player`, which declares the enum `State`
+fn nested_dump() -> DllData {
+    let mut dump = dump();
+    let player = add_type(&mut dump, "Game.Player", TypeEnum::Class);
+    let state = add_type(&mut dump, "State", TypeEnum::Enum);
+    dump.types[state].this.declaring_type = Some(reference(&dump, player));
+    dump.types[state].instance_fields = vec![field(&dump, "value__", "System.Int32", 0x10)];
+    dump
+}
+
+/// Generated code without whitespace, so assertions don't depend on the formatting
+fn compact(code: &str) -> String {
+    code.split_whitespace().collect()
+}
+
+#[test]
+fn nested_types() {
+    let dump = nested_dump();
+    let code = compact(
+        &Generator::new()
+            .include("Game.*")
+            .generate(&dump)
+            .unwrap()
+            .code,
+    );
+    assert!(code.contains("pubstructPlayer_State{"), "{}", code);
+    assert!(!code.contains("pubmodPlayer_"), "{}", code);
+
+    let generator = Generator::new().include("Game.*").nested_modules(true);
+    let code = compact(&generator.generate(&dump).unwrap().code);
+    assert!(
+        code.contains("pubmodGame{pubmodPlayer_{#[repr(C)]pubstructState{"),
+        "{}",
+        code
+    );
+    // The flat name stays available as an alias
+    assert!(
+        code.contains("pubtypePlayer_State=Player_::State;"),
+        "{}",
+        code
+    );
+}
+
+#[test]
+fn array_and_pointer_types() {
+    let mut dump = dump();
+    let int = type_ref(&dump, "System.Int32");
+    let mut array_types = Vec::new();
+    for name in ["System.Int32[]", "System.Int32[,]"] {
+        let id = add_type(&mut dump, name, TypeEnum::Class);
+        dump.types[id].this.element_type = Some(int.clone());
+        array_types.push(id);
+    }
+    let mut inner = reference(&dump, array_types[0]);
+    inner.is_array = true;
+    let jagged = add_type(&mut dump, "System.Int32[][]", TypeEnum::Class);
+    dump.types[jagged].this.element_type = Some(inner);
+
+    let player = add_type(&mut dump, "Game.Player", TypeEnum::Class);
+    let mut fields = Vec::new();
+    for (name, id) in [
+        ("scores", array_types[0]),
+        ("grid", array_types[1]),
+        ("rows", jagged),
+    ] {
+        let mut array = field(&dump, name, "System.Int32", 0x10);
+        array.field_type = reference(&dump, id);
+        array.field_type.is_array = true;
+        fields.push(array);
+    }
+    // Dumps without element types mark the reference to the element type instead
+    for (name, pointee) in [("count", "System.Int32"), ("data", "System.Void")] {
+        let mut pointer = field(&dump, name, pointee, 0x10);
+        pointer.field_type.is_pointer = true;
+        fields.push(pointer);
+    }
+    dump.types[player].instance_fields = fields;
+
+    let code = compact(
+        &Generator::new()
+            .include("Game.*")
+            .generate(&dump)
+            .unwrap()
+            .code,
+    );
+    for expected in [
+        "pubscores:*mutquest_hook::libil2cpp::Il2CppArray<i32>,",
+        "pubgrid:*mutcrate::__arrays::MultidimensionalArray<i32,2>,",
+        "pubrows:*mutquest_hook::libil2cpp::Il2CppArray<*mutquest_hook::libil2cpp::Il2CppArray<i32>",
+        "pubcount:*muti32,",
+        "pubdata:*mutstd::ffi::c_void,",
+    ] {
+        assert!(code.contains(expected), "{}\n{}", expected, code);
+    }
+}
+
+#[test]
+fn deterministic_order() {
+    let names = ["Zoo.Zebra", "Game.Player", "Zoo.Ape", "Game.Enemy"];
+    let generate = |names: &[&str]| {
+        let mut dump = dump();
+        for name in names {
+            add_type(&mut dump, name, TypeEnum::Class);
+        }
+        Generator::new().generate(&dump).unwrap().code
+    };
+    let code = generate(&names);
+    let mut reversed = names;
+    reversed.reverse();
+    assert_eq!(code, generate(&reversed));
+
+    let positions: Vec<_> = [
+        "pub mod Game",
+        "pub struct Enemy",
+        "pub struct Player",
+        "pub mod Zoo",
+        "pub struct Ape",
+        "pub struct Zebra",
+    ]
+    .iter()
+    .map(|item| code.find(item).unwrap())
+    .collect();
+    assert!(positions.windows(2).all(|w| w[0] < w[1]), "{}", code);
+}
+
+#[test]
+fn overloads_are_numbered_per_name() {
+    let mut dump = dump();
+    let player = add_type(&mut dump, "Game.Player", TypeEnum::Class);
+    let hit =
+        |dump: &DllData, ty| method(dump, "Hit", "System.Void", &[(ty, ParameterModifier::None)]);
+    dump.types[player].methods = vec![hit(&dump, "System.Int32"), hit(&dump, "System.Single")];
+    let code = compact(
+        &Generator::new()
+            .include("Game.*")
+            .generate(&dump)
+            .unwrap()
+            .code,
+    );
+
+    // Adding a method of another name does not rename the overloads
+    let kill = method(&dump, "Kill", "System.Void", &[]);
+    dump.types[player].methods.insert(0, kill);
+    let with_kill = compact(
+        &Generator::new()
+            .include("Game.*")
+            .generate(&dump)
+            .unwrap()
+            .code,
+    );
+    for code in [&code, &with_kill] {
+        assert!(code.contains("pubfnHit_0(&mutself,arg0:i32,)"), "{}", code);
+        assert!(code.contains("pubfnHit_1(&mutself,arg0:f32,)"), "{}", code);
+    }
+    assert!(with_kill.contains("pubfnKill_0(&mutself)"), "{}", with_kill);
+}